@@ -0,0 +1,29 @@
+//! Compares a full parse with `Dbc::parse_layout_only`, run with `cargo bench`
+
+use rs_dbc::Dbc;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const RUNS: u32 = 20;
+
+fn time(input: &str, parse: fn(&str) -> Dbc) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        black_box(parse(black_box(input)));
+    }
+    start.elapsed() / RUNS
+}
+
+fn main() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/sample.dbc");
+    let input = std::fs::read_to_string(path).unwrap();
+
+    let full = time(&input, |input| Dbc::try_from(input).unwrap());
+    let layout = time(&input, |input| Dbc::parse_layout_only(input).unwrap());
+    println!("full parse:        {:?}", full);
+    println!("parse_layout_only: {:?}", layout);
+    println!(
+        "speedup:           {:.1}x",
+        full.as_secs_f64() / layout.as_secs_f64()
+    );
+}
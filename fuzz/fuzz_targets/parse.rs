@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rs_dbc::Dbc;
+
+fuzz_target!(|data: &[u8]| {
+    // Parsing arbitrary bytes must never panic, only return an error
+    let _ = Dbc::from_slice(data);
+
+    if let Ok(dbc) = Dbc::from_slice_lossy(data) {
+        // Feed the same bytes back in as a frame payload
+        for msg in &dbc.messages {
+            let _ = msg.decode(data);
+            for sig in &msg.signals {
+                let _ = sig.vector_start_bit();
+            }
+        }
+    }
+});
@@ -31,29 +31,56 @@
 //! ```
 
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::convert::TryFrom;
+use std::ops::{Range, RangeInclusive};
 use std::str;
 
-use std::fmt;
+use std::fmt::{self, Write};
 
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum Error {
-    Invalid(Dbc, String),
+    Invalid(Box<Dbc>, String),
+    Utf8(str::Utf8Error),
+    Io(std::io::Error),
+    /// Merged databases define the same item differently
+    Conflicts(Box<Dbc>, Vec<MergeConflict>),
+    /// `ParseOptions::name_pattern` is not a valid regex or contains a capture group
+    NamePattern(String),
+    /// An `SG_` line on the given 1-based line is not a well-formed signal, see `ParseOptions::strict`
+    MalformedSignal(usize, String),
+    /// No message has the given ID
+    UnknownMessage(MessageID),
+    /// A message with the given ID already exists
+    DuplicateMessage(MessageID),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::Invalid(_, msg) => write!(f, "Invalid DBC input: {}", msg),
+            Error::Utf8(err) => write!(f, "DBC input is not valid UTF-8: {}", err),
+            Error::Io(err) => write!(f, "Failed to read DBC input: {}", err),
+            Error::Conflicts(_, conflicts) => {
+                let conflicts: Vec<String> = conflicts.iter().map(|c| c.to_string()).collect();
+                write!(f, "Conflicting definitions of {}", conflicts.join(", "))
+            }
+            Error::NamePattern(pattern) => write!(f, "Invalid name pattern: {}", pattern),
+            Error::MalformedSignal(line, text) => {
+                write!(f, "Malformed signal on line {}: {}", line, text)
+            }
+            Error::UnknownMessage(id) => write!(f, "Unknown message {}", id.to_hex_string()),
+            Error::DuplicateMessage(id) => {
+                write!(f, "Message {} already exists", id.to_hex_string())
+            }
         }
     }
 }
 
 impl std::error::Error for Error {}
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum MessageID {
     Standard(u16),
     Extended(u32),
@@ -67,12 +94,32 @@ impl MessageID {
         }
     }
 
+    /// Classifies an ID as written in a `BO_` line, where extended IDs have bit 31 set
+    fn from_dbc(id: u32) -> MessageID {
+        if id & (1 << 31) != 0 {
+            MessageID::Extended(id & 0x1FFF_FFFF)
+        } else if id < 0x800 {
+            MessageID::Standard(id as u16)
+        } else {
+            MessageID::Extended(id)
+        }
+    }
+
     pub fn kind(&self) -> &'static str {
         match self {
             MessageID::Standard(_) => "CAN Standard",
             MessageID::Extended(_) => "CAN Extended",
         }
     }
+
+    /// Formats the ID in hex as CANdb++ does: standard IDs padded to 3 digits (`0x100`),
+    /// extended IDs padded to 8 digits and suffixed with `x` (`0x18FEF100x`)
+    pub fn to_hex_string(&self) -> String {
+        match self {
+            MessageID::Standard(id) => format!("0x{:03X}", id),
+            MessageID::Extended(id) => format!("0x{:08X}x", id),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -113,7 +160,22 @@ impl fmt::Display for ValueType {
 pub enum MultiplexIndicator {
     Plain,
     Multiplexer,
-    Multiplexed,
+    /// Multiplexed signal, active when the multiplexer carries the given switch value
+    Multiplexed(u64),
+    /// Multiplexed signal that is itself the multiplexor of further signals (`m<n>M`),
+    /// as used for nested multiplexing
+    MultiplexedMultiplexer(u64),
+}
+
+impl MultiplexIndicator {
+    /// Returns the switch value of a multiplexed signal
+    fn switch_value(self) -> Option<u64> {
+        match self {
+            MultiplexIndicator::Multiplexed(value)
+            | MultiplexIndicator::MultiplexedMultiplexer(value) => Some(value),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for MultiplexIndicator {
@@ -121,7 +183,113 @@ impl fmt::Display for MultiplexIndicator {
         match self {
             MultiplexIndicator::Plain => write!(f, "Plain"),
             MultiplexIndicator::Multiplexer => write!(f, "Multiplexer"),
-            MultiplexIndicator::Multiplexed => write!(f, "Multiplexed"),
+            MultiplexIndicator::Multiplexed(_) => write!(f, "Multiplexed"),
+            MultiplexIndicator::MultiplexedMultiplexer(_) => write!(f, "MultiplexedMultiplexer"),
+        }
+    }
+}
+
+/// Object kind an attribute definition applies to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttributeObjectType {
+    Network,
+    Node,
+    Message,
+    Signal,
+    EnvironmentVariable,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttributeValueType {
+    Int(i64, i64),
+    /// Integer declared as `HEX`, meant to be displayed in hexadecimal
+    Hex(i64, i64),
+    Float(f64, f64),
+    String,
+    Enum(Vec<String>),
+}
+
+/// An attribute declared with `BA_DEF_`
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeDefinition {
+    pub name: String,
+    pub object_type: AttributeObjectType,
+    pub value_type: AttributeValueType,
+    /// Value from `BA_DEF_DEF_`
+    pub default: Option<AttributeValue>,
+}
+
+impl AttributeDefinition {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn object_type(&self) -> AttributeObjectType {
+        self.object_type
+    }
+
+    pub fn value_type(&self) -> &AttributeValueType {
+        &self.value_type
+    }
+
+    pub fn default(&self) -> Option<&AttributeValue> {
+        self.default.as_ref()
+    }
+}
+
+/// Node relation a `BA_DEF_REL_` attribute applies to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RelationType {
+    /// `BU_BO_REL_`
+    NodeMessage,
+    /// `BU_SG_REL_`
+    NodeSignal,
+    /// `BU_EV_REL_`
+    NodeEnvVar,
+}
+
+/// An attribute declared with `BA_DEF_REL_`
+#[derive(Clone, Debug, PartialEq)]
+pub struct RelationAttributeDefinition {
+    pub name: String,
+    pub relation_type: RelationType,
+    pub value_type: AttributeValueType,
+    /// Value from `BA_DEF_DEF_REL_`
+    pub default: Option<AttributeValue>,
+}
+
+/// Object on the other side of a node relation
+#[derive(Clone, Debug, PartialEq)]
+pub enum RelationTarget {
+    Message(MessageID),
+    Signal(MessageID, String),
+    EnvVar(String),
+}
+
+/// A `BA_REL_` value assigned to a node relation
+#[derive(Clone, Debug, PartialEq)]
+pub struct RelationAttribute {
+    pub name: String,
+    pub node: String,
+    pub target: RelationTarget,
+    pub value: AttributeValue,
+}
+
+/// An attribute value as written in `BA_` or `BA_DEF_DEF_`.
+/// Enum attributes hold the variant index or label exactly as written.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttributeValue {
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeValue::Int(value) => write!(f, "{}", value),
+            AttributeValue::Float(value) => write!(f, "{}", value),
+            AttributeValue::String(value) => write!(f, "{}", value),
         }
     }
 }
@@ -138,13 +306,51 @@ pub struct Signal {
     pub min: f64,
     pub max: f64,
     pub unit: String,
+    /// Receiving nodes, without `Vector__XXX` and `DUMMY_NODE_VECTOR` placeholders
     pub receivers: Vec<String>,
-    pub value_descriptions: HashMap<u64, String>,
+    /// `VAL_` descriptions keyed by raw value, which may be negative for signed signals
+    pub value_descriptions: HashMap<i64, String>,
     pub multiplexer_type: MultiplexIndicator,
+    /// Name of the multiplexor signal switching this signal, from `SG_MUL_VAL_`
+    /// or the message's `M` signal when unspecified
+    pub multiplexor_signal: Option<String>,
     pub initial_value: f64,
+    /// Resolved `GenSigSendType` label
+    pub send_type: Option<String>,
+    /// `GenSigILSupport`, whether the interaction layer of the COM stack manages the signal
+    pub il_support: Option<bool>,
+    /// Explicit `BA_` values assigned to this signal
+    pub attributes: HashMap<String, AttributeValue>,
+    pub comment: Option<String>,
 }
 
 impl Signal {
+    /// Creates an unsigned Intel signal with factor 1, offset 0, a `[0|0]` range,
+    /// no unit, receivers or value descriptions, and no multiplexing
+    pub fn new(name: &str, start_bit: u64, signal_size: u64) -> Signal {
+        Signal {
+            name: name.to_string(),
+            start_bit,
+            signal_size,
+            byte_order: ByteOrder::Intel,
+            value_type: ValueType::Unsigned,
+            factor: 1.0,
+            offset: 0.0,
+            min: 0.0,
+            max: 0.0,
+            unit: String::new(),
+            receivers: Vec::new(),
+            value_descriptions: HashMap::new(),
+            multiplexer_type: MultiplexIndicator::Plain,
+            multiplexor_signal: None,
+            initial_value: 0.0,
+            send_type: None,
+            il_support: None,
+            attributes: HashMap::new(),
+            comment: None,
+        }
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -153,24 +359,32 @@ impl Signal {
         self.start_bit
     }
 
-    /// Returns the start bit as displayed in Vector CANdb++
+    /// Returns the start bit as displayed in Vector CANdb++.
+    /// Intel signals show the DBC start bit, Motorola signals show their least significant bit,
+    /// so this is the start bit an Intel signal at the same position would have.
     pub fn vector_start_bit(&self) -> u64 {
         match self.byte_order {
             ByteOrder::Intel => self.start_bit,
+            ByteOrder::Motorola => self.occupied_bits().last().unwrap_or(self.start_bit),
+        }
+    }
+
+    /// Converts a start bit as displayed in Vector CANdb++ back to the DBC `start_bit`,
+    /// the inverse of [`Signal::vector_start_bit`]
+    pub fn canonical_from_vector(vector_bit: u64, size: u64, order: ByteOrder) -> u64 {
+        match order {
+            ByteOrder::Intel => vector_bit,
             ByteOrder::Motorola => {
-                let start_byte = self.start_bit / 8;
-                let start_bit_in_byte = self.start_bit % 8;
-                let end_bit_1 = self.start_bit + 1;
-                let end_bit_2 = self.start_bit.saturating_sub(self.signal_size - 1);
-                let end_byte = end_bit_1 / 8;
-
-                if start_byte != end_byte && self.signal_size > 8 {
-                    end_bit_1
-                } else if start_bit_in_byte != 8 {
-                    end_bit_2
-                } else {
-                    self.start_bit
+                // Walk from the least significant bit back up to the most significant one
+                let mut bit = vector_bit;
+                for _ in 1..size.min(64 * 8) {
+                    bit = if bit % 8 == 7 {
+                        bit.saturating_sub(15)
+                    } else {
+                        bit.saturating_add(1)
+                    };
                 }
+                bit
             }
         }
     }
@@ -195,6 +409,23 @@ impl Signal {
         self.offset
     }
 
+    /// Returns the smallest physical step, `|factor|`
+    pub fn resolution(&self) -> f64 {
+        self.factor.abs()
+    }
+
+    /// Returns the number of decimals at which a single physical step becomes visible,
+    /// e.g. 2 for a factor of 0.01 and 0 for integer factors or a factor of 0
+    pub fn decimal_places(&self) -> usize {
+        let resolution = self.resolution();
+        if resolution == 0.0 || !resolution.is_finite() {
+            return 0;
+        }
+        // The epsilon keeps exact powers of ten like 0.01 from rounding up a place
+        let places = (-resolution.log10() - 1e-9).ceil();
+        places.max(0.0) as usize
+    }
+
     pub fn min(&self) -> f64 {
         self.min
     }
@@ -203,250 +434,3603 @@ impl Signal {
         self.max
     }
 
+    /// Returns the declared `(min, max)` range, or `None` when the DBC leaves it at `[0|0]`,
+    /// the convention for an unspecified range
+    pub fn declared_range(&self) -> Option<(f64, f64)> {
+        if self.min == 0.0 && self.max == 0.0 {
+            None
+        } else {
+            Some((self.min, self.max))
+        }
+    }
+
+    /// Returns the physical range the raw value can represent given
+    /// `signal_size`, `value_type`, `factor` and `offset`
+    fn derived_range(&self) -> (f64, f64) {
+        let size = self.signal_size.min(64) as i32;
+        let (raw_min, raw_max) = match self.value_type {
+            ValueType::Unsigned => (0.0, 2f64.powi(size) - 1.0),
+            ValueType::Signed if size == 0 => (0.0, 0.0),
+            ValueType::Signed => (-(2f64.powi(size - 1)), 2f64.powi(size - 1) - 1.0),
+            ValueType::Float => (f32::MIN as f64, f32::MAX as f64),
+            ValueType::Double => (f64::MIN, f64::MAX),
+        };
+        let a = (raw_min * self.factor) + self.offset;
+        let b = (raw_max * self.factor) + self.offset;
+        (a.min(b), a.max(b))
+    }
+
+    /// Changes the factor and offset of the signal.
+    /// When `min`/`max` describe the full range of the old scaling (within half a raw step)
+    /// they are recomputed for the new scaling; any other bounds, including `[0|0]`, are left verbatim.
+    pub fn set_scaling(&mut self, factor: f64, offset: f64) {
+        let (old_min, old_max) = self.derived_range();
+        let tolerance = self.factor.abs() / 2.0;
+        let full_range = !(self.min == 0.0 && self.max == 0.0)
+            && (self.min - old_min).abs() <= tolerance
+            && (self.max - old_max).abs() <= tolerance;
+
+        self.factor = factor;
+        self.offset = offset;
+
+        if full_range {
+            (self.min, self.max) = self.derived_range();
+        }
+    }
+
+    /// Returns the declared minimum, or the lowest physical value the signal can
+    /// represent when the DBC leaves the range at `[0|0]`
+    pub fn effective_min(&self) -> f64 {
+        self.declared_range()
+            .map_or_else(|| self.derived_range().0, |(min, _)| min)
+    }
+
+    /// Returns the declared maximum, or the highest physical value the signal can
+    /// represent when the DBC leaves the range at `[0|0]`
+    pub fn effective_max(&self) -> f64 {
+        self.declared_range()
+            .map_or_else(|| self.derived_range().1, |(_, max)| max)
+    }
+
+    /// Returns whether a decoded physical value lies within the declared `[min|max]` range.
+    /// A `[0|0]` range is treated as unbounded, so every value is in range.
+    pub fn is_in_range(&self, physical: f64) -> bool {
+        self.declared_range()
+            .is_none_or(|(min, max)| (min..=max).contains(&physical))
+    }
+
+    /// Returns a non-empty `(min, max)` physical range for display.
+    /// The declared `[min|max]` wins when `min < max`; otherwise the range the raw value can
+    /// represent is used, and if that is empty too (zero size or factor) the span is widened to 1.
+    pub fn physical_span(&self) -> (f64, f64) {
+        if self.min < self.max {
+            return (self.min, self.max);
+        }
+        let (min, max) = self.derived_range();
+        if min < max {
+            (min, max)
+        } else {
+            (min, min + 1.0)
+        }
+    }
+
     pub fn unit(&self) -> &str {
         &self.unit
     }
 
+    /// Returns the unit with surrounding whitespace removed and common spellings replaced by
+    /// one form, e.g. `kph` by `km/h` and `degC` by `°C`, see [`Dbc::normalize_units`]
+    pub fn normalized_unit(&self) -> String {
+        let unit = self.unit.trim();
+        UNIT_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == unit)
+            .map_or(unit, |(_, normalized)| normalized)
+            .to_string()
+    }
+
+    /// Returns whether both signals use the same unit after normalization
+    pub fn units_match(&self, other: &Signal) -> bool {
+        self.normalized_unit() == other.normalized_unit()
+    }
+
     pub fn receivers(&self) -> &Vec<String> {
         &self.receivers
     }
 
-    pub fn value_descriptions(&self) -> &HashMap<u64, String> {
+    pub fn value_descriptions(&self) -> &HashMap<i64, String> {
         &self.value_descriptions
     }
 
+    /// Returns the value descriptions sorted by value, for deterministic output
+    pub fn sorted_value_descriptions(&self) -> Vec<(i64, &str)> {
+        let mut desc: Vec<_> = self
+            .value_descriptions
+            .iter()
+            .map(|(&value, description)| (value, description.as_str()))
+            .collect();
+        desc.sort_by_key(|&(value, _)| value);
+        desc
+    }
+
     pub fn multiplexer_type(&self) -> MultiplexIndicator {
         self.multiplexer_type
     }
 
+    /// Returns whether this signal switches other signals (`M` or `m<n>M`)
+    pub fn is_multiplexor(&self) -> bool {
+        matches!(
+            self.multiplexer_type,
+            MultiplexIndicator::Multiplexer | MultiplexIndicator::MultiplexedMultiplexer(_)
+        )
+    }
+
+    /// Returns whether this signal is only present for a specific multiplexor value (`m<n>` or `m<n>M`)
+    pub fn is_multiplexed(&self) -> bool {
+        self.multiplexer_type.switch_value().is_some()
+    }
+
+    pub fn multiplexor_signal(&self) -> Option<&str> {
+        self.multiplexor_signal.as_deref()
+    }
+
     pub fn initial_value(&self) -> f64 {
         self.initial_value
     }
 
+    pub fn send_type(&self) -> Option<&str> {
+        self.send_type.as_deref()
+    }
+
+    pub fn il_support(&self) -> Option<bool> {
+        self.il_support
+    }
+
+    pub fn attributes(&self) -> &HashMap<String, AttributeValue> {
+        &self.attributes
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
     /// Returns the initial value as displayed in Vector CANdb++
     /// Formula: (Raw value × factor) + offset
     pub fn vector_initial_value(&self) -> f64 {
         (self.initial_value * self.factor) + self.offset
     }
 
+    /// Returns the physical initial value. An explicit `GenSigStartValuePhys` attribute, which some
+    /// tools write already scaled, takes precedence over the raw `GenSigStartValue`, which is
+    /// scaled as by [`Signal::vector_initial_value`].
+    pub fn physical_initial_value(&self) -> f64 {
+        match self.attributes.get("GenSigStartValuePhys") {
+            Some(AttributeValue::Int(value)) => *value as f64,
+            Some(AttributeValue::Float(value)) => *value,
+            _ => self.vector_initial_value(),
+        }
+    }
+
     /// Returns the value descriptions sorted by value and formatted as hex strings
     pub fn vector_value_descriptions(&self) -> Vec<(String, String)> {
         let mut desc: Vec<_> = self.value_descriptions.iter().collect();
         desc.sort_by_key(|a| a.0);
         desc.into_iter()
-            .map(|(&k, v)| (format!("0x{:X}", k), v.clone()))
+            .map(|(&k, v)| {
+                let hex = if k < 0 {
+                    format!("-0x{:X}", k.unsigned_abs())
+                } else {
+                    format!("0x{:X}", k)
+                };
+                (hex, v.clone())
+            })
             .collect()
     }
-}
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct Message {
-    pub message_name: String,
-    pub message_id: MessageID,
-    pub message_size: u64,
-    pub cycle_time: u32,
-    pub transmitter: String,
-    pub tx_method: String,
-    pub signals: Vec<Signal>,
-}
+    /// Returns the payload bits occupied by the signal, numbered `byte * 8 + bit`
+    /// with bit 0 being the least significant bit of a byte.
+    /// No signal can cover more bits than the largest CAN FD payload (64 bytes).
+    fn occupied_bits(&self) -> impl Iterator<Item = u64> + '_ {
+        let mut bit = self.start_bit;
+        (0..self.signal_size.min(64 * 8)).map(move |_| {
+            let current = bit;
+            bit = match self.byte_order {
+                ByteOrder::Intel => bit.saturating_add(1),
+                // Motorola signals continue at the most significant bit of the next byte
+                ByteOrder::Motorola if bit.is_multiple_of(8) => bit.saturating_add(15),
+                ByteOrder::Motorola => bit - 1,
+            };
+            current
+        })
+    }
 
-impl Message {
-    pub fn message_name(&self) -> &str {
-        &self.message_name
+    /// Returns the span of payload bits covered by the signal, numbered `byte * 8 + bit`.
+    /// Motorola signals not aligned to a byte boundary leave gaps inside this span.
+    pub fn bit_range(&self) -> Range<u64> {
+        match self.byte_order {
+            ByteOrder::Intel => {
+                self.start_bit..self.start_bit.saturating_add(self.signal_size.min(64 * 8))
+            }
+            ByteOrder::Motorola => {
+                let (low, high) = self
+                    .occupied_bits()
+                    .fold((u64::MAX, 0), |(low, high), bit| {
+                        (low.min(bit), high.max(bit))
+                    });
+                if low > high {
+                    self.start_bit..self.start_bit
+                } else {
+                    low..high + 1
+                }
+            }
+        }
     }
 
-    pub fn message_id(&self) -> (u32, &'static str) {
-        (self.message_id.raw(), self.message_id.kind())
+    /// Returns the indices of the first and last payload byte the signal touches
+    pub fn byte_range(&self) -> RangeInclusive<u64> {
+        let bits = self.bit_range();
+        let last_bit = bits.end.saturating_sub(1).max(bits.start);
+        (bits.start / 8)..=(last_bit / 8)
     }
 
-    pub fn message_size(&self) -> u64 {
-        self.message_size
+    /// Returns whether the signal covers whole bytes, i.e. its least significant bit
+    /// (see [`Signal::vector_start_bit`]) starts a byte and its size is a multiple of 8
+    pub fn is_byte_aligned(&self) -> bool {
+        self.vector_start_bit().is_multiple_of(8) && self.signal_size.is_multiple_of(8)
     }
 
-    pub fn cycle_time(&self) -> u32 {
-        self.cycle_time
+    /// Returns the number of payload bytes the signal touches
+    pub fn spans_bytes(&self) -> u64 {
+        let bytes = self.byte_range();
+        bytes.end() - bytes.start() + 1
     }
 
-    pub fn transmitter(&self) -> &str {
-        if self.transmitter.starts_with("Vector__XXX") {
-            "No Transmitter"
-        } else {
-            &self.transmitter
+    /// Returns the raw value of the signal as opaque bytes, for wide fields such as text, in signal
+    /// byte order: least significant byte first for Intel, most significant first for Motorola.
+    /// A size that is not a multiple of 8 leaves the most significant byte partial, and bits
+    /// past the end of the payload read as zero. Unlike [`Signal::decode`] this is not limited to 64 bits.
+    pub fn byte_chunks(&self, data: &[u8]) -> Vec<u8> {
+        let mut bits: Vec<u64> = self.occupied_bits().collect();
+        if self.byte_order == ByteOrder::Motorola {
+            // Motorola signals are walked from their most significant bit
+            bits.reverse();
         }
+        let mut chunks = vec![0u8; bits.len().div_ceil(8)];
+        for (i, bit) in bits.into_iter().enumerate() {
+            let set = data
+                .get((bit / 8) as usize)
+                .is_some_and(|byte| byte & (1 << (bit % 8)) != 0);
+            if set {
+                chunks[i / 8] |= 1 << (i % 8);
+            }
+        }
+        if self.byte_order == ByteOrder::Motorola {
+            chunks.reverse();
+        }
+        chunks
     }
 
-    pub fn tx_method(&self) -> &str {
-        &self.tx_method
+    /// Extracts the raw value of the signal from a classic or CAN FD payload,
+    /// sign-extended for signed signals
+    fn extract_raw(&self, data: &[u8]) -> i64 {
+        extract_bits(
+            data,
+            self.start_bit,
+            self.signal_size,
+            self.byte_order,
+            self.value_type == ValueType::Signed,
+        )
     }
-}
-
-#[derive(Clone, Debug, PartialEq)]
-pub struct Dbc {
-    pub messages: Vec<Message>,
-}
 
-impl Dbc {
-    pub fn from_slice(buffer: &[u8]) -> Result<Dbc, Error> {
-        let dbc_input = str::from_utf8(buffer).unwrap();
-        Self::try_from(dbc_input)
+    /// Returns the payload length in bytes needed to hold every bit of the signal
+    fn required_bytes(&self) -> usize {
+        self.occupied_bits()
+            .max()
+            .map_or(0, |bit| (bit / 8 + 1) as usize)
     }
 
-    pub fn from_slice_lossy(buffer: &[u8]) -> Result<Dbc, Error> {
-        let dbc_input = String::from_utf8_lossy(buffer);
-        Self::try_from(dbc_input.as_ref())
+    /// Returns whether every bit of the signal lies within the received payload
+    fn is_received(&self, data: &[u8]) -> bool {
+        let received_bits = (data.len() as u64).saturating_mul(8);
+        self.occupied_bits().all(|bit| bit < received_bits)
     }
-}
 
-impl TryFrom<&str> for Dbc {
-    type Error = Error;
+    /// Decodes the physical value of the signal from a frame payload
+    /// Formula: (Raw value × factor) + offset
+    /// IEEE signals take their raw bits as an `f32`/`f64` instead of an integer
+    pub fn decode(&self, data: &[u8]) -> f64 {
+        self.raw_to_physical(self.extract_raw(data))
+    }
 
-    fn try_from(dbc_input: &str) -> Result<Self, Self::Error> {
-        let messages = parse_message(dbc_input);
+    /// Scales a raw value as extracted by [`Signal::decode`]
+    fn raw_to_physical(&self, raw: i64) -> f64 {
+        let value = match (self.value_type, self.signal_size) {
+            (ValueType::Float, 32) => f32::from_bits(raw as u32) as f64,
+            (ValueType::Double, 64) => f64::from_bits(raw as u64),
+            _ => raw as f64,
+        };
+        (value * self.factor) + self.offset
+    }
 
-        if messages.is_empty() {
-            return Err(Error::Invalid(Dbc { messages }, dbc_input.to_string()));
+    /// Converts a physical value back to the raw value, the inverse of [`Signal::decode`].
+    /// Formula: (Physical value - offset) / factor, rounded to the nearest integer.
+    /// IEEE signals yield the bits of the `f32`/`f64`. A factor of 0 yields a raw value of 0,
+    /// since every raw value decodes to the offset.
+    pub fn physical_to_raw(&self, physical: f64) -> i64 {
+        let value = if self.factor == 0.0 {
+            0.0
+        } else {
+            (physical - self.offset) / self.factor
+        };
+        match (self.value_type, self.signal_size) {
+            (ValueType::Float, 32) => (value as f32).to_bits() as i64,
+            (ValueType::Double, 64) => value.to_bits() as i64,
+            _ => value.round() as i64,
         }
-        Ok(Dbc { messages })
     }
-}
 
-fn parse_message(dbc_input: &str) -> Vec<Message> {
-    let message_names = parse_message_name(dbc_input);
-    let message_size = parse_message_size(dbc_input);
-    let message_transmitters = parse_message_transmitters(dbc_input);
-    let default_cycles = parse_default_cycle_time(dbc_input).unwrap_or(0);
-    let explicit_cycles = parse_explicit_cycle_time(dbc_input);
-    let (default_tx_method, explicit_tx_methods) = parse_tx_methods(dbc_input);
-    let value_descriptions = parse_value_descriptions(dbc_input);
-    let signals = parse_signals(dbc_input, &value_descriptions);
-
-    let mut message = Vec::new();
+    /// Writes the physical value into a frame payload, the inverse of [`Signal::decode`].
+    /// Bits of other signals are left untouched.
+    pub fn encode(&self, physical: f64, data: &mut [u8]) {
+        insert_bits(
+            data,
+            self.start_bit,
+            self.signal_size,
+            self.byte_order,
+            self.physical_to_raw(physical),
+        );
+    }
 
-    for (id, message_name) in message_names {
-        let cycle_time = explicit_cycles.get(&id).copied().unwrap_or(default_cycles);
-        let message_size = message_size.get(&id).copied().unwrap_or(0);
-        let message_signals = signals.get(&id).cloned().unwrap_or_else(Vec::new);
-        let transmitter = message_transmitters
-            .get(&id)
-            .cloned()
-            .unwrap_or_else(|| "Vector__XXX".to_string());
-        let tx_method = explicit_tx_methods
-            .get(&id)
-            .cloned()
-            .unwrap_or_else(|| default_tx_method.clone());
+    /// Returns the value description matching the raw value in the payload, if any.
+    /// Signed raw values match a negative key as well as the key of their unsigned bit pattern.
+    pub fn decode_enum(&self, data: &[u8]) -> Option<&str> {
+        self.describe(self.extract_raw(data))
+    }
 
-        let message_id = if id < 0x800 {
-            MessageID::Standard(id as u16)
+    /// Looks up the value description of a raw value as extracted by [`Signal::decode_enum`]
+    fn describe(&self, raw: i64) -> Option<&str> {
+        let unsigned = if self.signal_size >= 64 {
+            raw
         } else {
-            MessageID::Extended(id)
+            (raw as u64 & ((1u64 << self.signal_size) - 1)) as i64
         };
-
-        message.push(Message {
-            message_name,
-            message_id,
-            message_size,
-            cycle_time,
-            transmitter,
-            tx_method,
-            signals: message_signals,
-        });
+        self.value_descriptions
+            .get(&raw)
+            .or_else(|| self.value_descriptions.get(&unsigned))
+            .map(String::as_str)
     }
 
-    message
-}
-
-fn parse_message_name(dbc_input: &str) -> HashMap<u32, String> {
-    let re_name = Regex::new(r#"BO_\s+(\d+)\s+(\w+):"#).unwrap();
-    let mut map = HashMap::new();
+    /// Scales a raw value with exact decimal arithmetic.
+    /// Factor and offset are taken as their shortest decimal form, so a factor of `0.1` is exactly one tenth.
+    /// Returns `None` when a factor or offset such as `1e300` or `1e-30` or the result does not fit a `Decimal`.
+    #[cfg(feature = "decimal")]
+    pub fn raw_to_decimal(&self, raw: i64) -> Option<rust_decimal::Decimal> {
+        use rust_decimal::Decimal;
 
-    for cap in re_name.captures_iter(dbc_input) {
-        if let (Ok(id), Ok(name)) = (cap[1].parse::<u32>(), cap[2].parse::<String>()) {
-            map.insert(id, name);
-        }
+        let exact = |value: f64| Decimal::from_str_exact(&value.to_string()).ok();
+        Decimal::from(raw)
+            .checked_mul(exact(self.factor)?)?
+            .checked_add(exact(self.offset)?)
     }
-    map
 }
 
-fn parse_message_size(dbc_input: &str) -> HashMap<u32, u64> {
-    let re_size = Regex::new(r#"BO_\s+(\d+)\s+\w+:\s+(\d+)"#).unwrap();
-    let mut map = HashMap::new();
+/// Extracts a raw value from a classic or CAN FD payload, as done when decoding signals.
+///
+/// Bits are numbered `byte * 8 + bit`, bit 0 being the least significant bit of the first byte.
+/// For Intel order `start_bit` is the least significant bit of the value, for Motorola order it
+/// is the most significant one, as in a DBC `SG_` line. Only the bytes from the first byte of the
+/// value on are read, so values may lie anywhere in the payload. Missing payload bytes read as
+/// zero, and values of zero or more than 64 bits yield 0.
+/// Signed values are sign-extended from `size` bits.
+pub fn extract_bits(
+    data: &[u8],
+    start_bit: u64,
+    size: u64,
+    byte_order: ByteOrder,
+    signed: bool,
+) -> i64 {
+    if size == 0 || size > 64 {
+        return 0;
+    }
 
-    for cap in re_size.captures_iter(dbc_input) {
-        if let (Ok(id), Ok(size)) = (cap[1].parse::<u32>(), cap[2].parse::<u64>()) {
-            map.insert(id, size);
+    let mask = if size == 64 {
+        u64::MAX
+    } else {
+        (1u64 << size) - 1
+    };
+    let window = bit_window(data, start_bit);
+
+    let raw = match byte_order {
+        ByteOrder::Intel => (u128::from_le_bytes(window) >> (start_bit % 8)) as u64 & mask,
+        ByteOrder::Motorola => {
+            // Position of the least significant bit when the window is read
+            // as one big-endian integer, counting from its top bit
+            let lsb = (7 - start_bit % 8) + size - 1;
+            (u128::from_be_bytes(window) >> (127 - lsb)) as u64 & mask
         }
+    };
+
+    if signed && size < 64 && raw & (1 << (size - 1)) != 0 {
+        (raw | !mask) as i64
+    } else {
+        raw as i64
     }
-    map
 }
 
-fn parse_message_transmitters(dbc_input: &str) -> HashMap<u32, String> {
-    let re_transmitter = Regex::new(r#"BO_\s+(\d+)\s+\w+:\s+\d+\s+(\w+)"#).unwrap();
-    let mut map = HashMap::new();
+/// Writes a raw value into a classic or CAN FD payload, the inverse of [`extract_bits`].
+///
+/// Bits are numbered as for [`extract_bits`]. Only the lowest `size` bits of `raw` are written,
+/// bits past the end of the payload are dropped, and values of zero or more than 64 bits leave
+/// the payload unchanged.
+pub fn insert_bits(data: &mut [u8], start_bit: u64, size: u64, byte_order: ByteOrder, raw: i64) {
+    if size == 0 || size > 64 {
+        return;
+    }
 
-    for cap in re_transmitter.captures_iter(dbc_input) {
-        if let Ok(id) = cap[1].parse::<u32>() {
-            let transmitter = cap[2].to_string();
-            map.insert(id, transmitter);
+    let mask = if size == 64 {
+        u64::MAX
+    } else {
+        (1u64 << size) - 1
+    };
+    let value = raw as u64 & mask;
+    let window = bit_window(data, start_bit);
+
+    let window = match byte_order {
+        ByteOrder::Intel => {
+            let shift = start_bit % 8;
+            let bits = u128::from_le_bytes(window);
+            ((bits & !(u128::from(mask) << shift)) | (u128::from(value) << shift)).to_le_bytes()
+        }
+        ByteOrder::Motorola => {
+            let shift = 127 - ((7 - start_bit % 8) + size - 1);
+            let bits = u128::from_be_bytes(window);
+            ((bits & !(u128::from(mask) << shift)) | (u128::from(value) << shift)).to_be_bytes()
         }
+    };
+
+    let first = (start_bit / 8) as usize;
+    if first < data.len() {
+        let len = (data.len() - first).min(16);
+        data[first..first + len].copy_from_slice(&window[..len]);
     }
-    map
 }
 
-fn parse_default_cycle_time(dbc_input: &str) -> Option<u32> {
-    let re_default = Regex::new(r#"BA_DEF_DEF_\s+"GenMsgCycleTime"\s+(\d+);"#).unwrap();
-    if let Some(cap) = re_default.captures(dbc_input) {
-        return cap[1].parse::<u32>().ok();
+/// Copies the 16 payload bytes starting at the byte of `start_bit`, zero-padded past the end.
+/// A value of up to 64 bits starting anywhere in its first byte always fits.
+fn bit_window(data: &[u8], start_bit: u64) -> [u8; 16] {
+    let mut window = [0u8; 16];
+    let first = usize::try_from(start_bit / 8).unwrap_or(usize::MAX);
+    if let Some(bytes) = data.get(first..) {
+        let len = bytes.len().min(16);
+        window[..len].copy_from_slice(&bytes[..len]);
     }
-    None
+    window
 }
 
-fn parse_explicit_cycle_time(dbc_input: &str) -> HashMap<u32, u32> {
-    let re_explicit = Regex::new(r#"BA_ "GenMsgCycleTime" BO_ (\d+) (\d+);"#).unwrap();
-    let mut map = HashMap::new();
+/// Unit spellings replaced by [`Signal::normalized_unit`]
+const UNIT_ALIASES: &[(&str, &str)] = &[
+    ("kph", "km/h"),
+    ("kmh", "km/h"),
+    ("kmph", "km/h"),
+    ("km/hr", "km/h"),
+    ("degC", "°C"),
+    ("deg C", "°C"),
+    ("DegC", "°C"),
+    ("degF", "°F"),
+    ("deg F", "°F"),
+    ("deg", "°"),
+    ("Deg", "°"),
+    ("degree", "°"),
+    ("degrees", "°"),
+    ("RPM", "rpm"),
+    ("1/min", "rpm"),
+    ("sec", "s"),
+    ("msec", "ms"),
+    ("volt", "V"),
+    ("Volt", "V"),
+    ("amp", "A"),
+    ("Amp", "A"),
+    ("pct", "%"),
+    ("percent", "%"),
+];
 
-    for cap in re_explicit.captures_iter(dbc_input) {
-        if let (Ok(id), Ok(cycle)) = (cap[1].parse::<u32>(), cap[2].parse::<u32>()) {
-            map.insert(id, cycle);
-        }
-    }
-    map
+/// Payload lengths in bytes of the CAN FD data length codes 0 to 15
+const FD_PAYLOAD_LENGTHS: [u64; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 12, 16, 20, 24, 32, 48, 64];
+
+/// Returns the data length code of the smallest CAN FD payload holding `len` bytes
+fn dlc_for_length(len: u64) -> u8 {
+    FD_PAYLOAD_LENGTHS
+        .iter()
+        .position(|&fd_len| fd_len >= len)
+        .unwrap_or(15) as u8
 }
 
-fn parse_signals(
-    dbc_input: &str,
-    value_descriptions: &HashMap<(u32, String), HashMap<u64, String>>,
-) -> HashMap<u32, Vec<Signal>> {
-    let re_signal = Regex::new(r#"SG_\s+(\w+)\s*([mM]?\d*)\s*:\s*(\d+)\|(\d+)@([01])([+-])\s*\(([^,]+),([^)]+)\)\s*\[([^|]+)\|([^\]]+)\]\s*"([^"]*)"\s*(.*)"#).unwrap();
-    let initial_values = parse_initial_values(dbc_input);
-    let sig_valtypes = parse_sig_valtypes(dbc_input);
-    let mut signals_map: HashMap<u32, Vec<Signal>> = HashMap::new();
-    let mut current_message_id = 0u32;
-    let lines: Vec<&str> = dbc_input.lines().collect();
+/// Formats a frame for display as `0x100 [8] 01 02 03 04 05 06 07 08`: the ID as by
+/// [`MessageID::to_hex_string`], the payload length in brackets, then the bytes in hex
+pub fn format_frame(id: MessageID, data: &[u8]) -> String {
+    let mut out = format!("{} [{}]", id.to_hex_string(), data.len());
+    for byte in data {
+        // Writing into a String cannot fail
+        let _ = write!(out, " {:02X}", byte);
+    }
+    out
+}
 
-    for line in lines {
-        if let Some(msg_cap) = Regex::new(r#"BO_\s+(\d+)\s+\w+:"#).unwrap().captures(line)
-            && let Ok(id) = msg_cap[1].parse::<u32>()
-        {
-            current_message_id = id;
-            signals_map.entry(current_message_id).or_default();
-        }
-
-        if let Some(cap) = re_signal.captures(line)
-            && let (Ok(start_bit), Ok(signal_size), Ok(factor), Ok(offset), Ok(min), Ok(max)) = (
-                cap[3].parse::<u64>(),
-                cap[4].parse::<u64>(),
-                cap[7].parse::<f64>(),
-                cap[8].parse::<f64>(),
-                cap[9].parse::<f64>(),
-                cap[10].parse::<f64>(),
-            )
-        {
-            let signal_name = cap[1].to_string();
-            let byte_order = if &cap[5] == "1" {
-                ByteOrder::Intel
-            } else {
-                ByteOrder::Motorola
-            };
+/// A `BO_` message with its signals.
+/// Only `PartialEq` is implemented since signals carry `f64` fields, which cannot be `Eq` or `Hash`;
+/// use [`Message::identity_key`] to deduplicate messages or collect them into a `HashMap`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    pub message_name: String,
+    pub message_id: MessageID,
+    /// Payload length in bytes. A CAN FD length code in the `BO_` line is decoded, see `dlc`.
+    pub message_size: u64,
+    /// Data length code of the payload. For FD messages whose `BO_` size is a length code rather
+    /// than a byte count (9 to 15 except 12, e.g. 15 for 64 bytes) this is the code as written.
+    pub dlc: u8,
+    /// Whether the `VFrameFormat` attribute marks the message as a CAN FD frame
+    pub is_fd: bool,
+    /// `GenMsgCycleTime` in ms, 0 when unspecified
+    pub cycle_time: u32,
+    /// `GenMsgCycleTime` in ms, `None` when neither a `BA_` nor a `BA_DEF_DEF_` value is present
+    pub cycle_time_opt: Option<u32>,
+    /// `GenMsgStartDelayTime` in ms
+    pub start_delay_time: Option<u32>,
+    /// `GenMsgNrOfRepetition`, the number of repetitions of an event-triggered message
+    pub nr_of_repetitions: Option<u32>,
+    /// `GenMsgILSupport`, whether the interaction layer of the COM stack manages the message
+    pub il_support: Option<bool>,
+    /// `NmMessage`, whether this is a network management message
+    pub nm_message: Option<bool>,
+    /// `DiagRequest`, whether this is a diagnostic request
+    pub diag_request: Option<bool>,
+    /// `DiagResponse`, whether this is a diagnostic response
+    pub diag_response: Option<bool>,
+    /// `DiagState`, whether this is a diagnostic state message
+    pub diag_state: Option<bool>,
+    pub transmitter: String,
+    /// Further transmitters listed in `BO_TX_BU_`, without `transmitter` and placeholders
+    pub transmitters: Vec<String>,
+    pub tx_method: String,
+    pub signals: Vec<Signal>,
+    /// Explicit `BA_` values assigned to this message
+    pub attributes: HashMap<String, AttributeValue>,
+    pub comment: Option<String>,
+}
+
+impl Message {
+    /// Creates a message without signals, sent by `Vector__XXX` with no cycle time or send type
+    pub fn new(name: &str, id: MessageID, size: u64) -> Message {
+        Message {
+            message_name: name.to_string(),
+            message_id: id,
+            message_size: size,
+            dlc: dlc_for_length(size),
+            is_fd: false,
+            cycle_time: 0,
+            cycle_time_opt: None,
+            start_delay_time: None,
+            nr_of_repetitions: None,
+            il_support: None,
+            nm_message: None,
+            diag_request: None,
+            diag_response: None,
+            diag_state: None,
+            transmitter: "Vector__XXX".to_string(),
+            transmitters: Vec::new(),
+            tx_method: String::new(),
+            signals: Vec::new(),
+            attributes: HashMap::new(),
+            comment: None,
+        }
+    }
+
+    pub fn message_name(&self) -> &str {
+        &self.message_name
+    }
+
+    pub fn message_id(&self) -> (u32, &'static str) {
+        (self.message_id.raw(), self.message_id.kind())
+    }
+
+    /// Returns a copy of the message with the given ID, e.g. to derive a response from a request layout
+    pub fn with_id(&self, new_id: MessageID) -> Message {
+        Message {
+            message_id: new_id,
+            ..self.clone()
+        }
+    }
+
+    /// Returns the message ID formatted by [`MessageID::to_hex_string`]
+    pub fn id_hex(&self) -> String {
+        self.message_id.to_hex_string()
+    }
+
+    /// Returns the ID identifying the message within a database, usable as a `HashMap` key
+    pub fn identity_key(&self) -> MessageID {
+        self.message_id
+    }
+
+    pub fn message_size(&self) -> u64 {
+        self.message_size
+    }
+
+    /// Returns the length of the frame buffers [`Message::encode`] and [`Message::layout_grid`]
+    /// build: `message_size`, capped at the 64 bytes of a CAN FD frame so that a corrupt size
+    /// such as `BO_ 1 X: 4294967295 N` cannot cause a huge allocation
+    pub fn payload_len(&self) -> usize {
+        self.message_size.min(FD_PAYLOAD_LENGTHS[15]) as usize
+    }
+
+    pub fn signal_count(&self) -> usize {
+        self.signals.len()
+    }
+
+    pub fn dlc(&self) -> u8 {
+        self.dlc
+    }
+
+    pub fn is_fd(&self) -> bool {
+        self.is_fd
+    }
+
+    pub fn cycle_time(&self) -> u32 {
+        self.cycle_time
+    }
+
+    /// Returns the cycle time, distinguishing an unspecified one from an explicit 0
+    pub fn cycle_time_opt(&self) -> Option<u32> {
+        self.cycle_time_opt
+    }
+
+    pub fn start_delay_time(&self) -> Option<u32> {
+        self.start_delay_time
+    }
+
+    pub fn nr_of_repetitions(&self) -> Option<u32> {
+        self.nr_of_repetitions
+    }
+
+    pub fn il_support(&self) -> Option<bool> {
+        self.il_support
+    }
+
+    pub fn transmitter(&self) -> &str {
+        if self.transmitter.starts_with("Vector__XXX") {
+            "No Transmitter"
+        } else {
+            &self.transmitter
+        }
+    }
+
+    pub fn transmitters(&self) -> &Vec<String> {
+        &self.transmitters
+    }
+
+    /// Returns every node sending the message: the `BO_` transmitter followed by the `BO_TX_BU_`
+    /// nodes, each listed once and without `Vector__XXX`
+    pub fn senders(&self) -> Vec<&str> {
+        let mut senders: Vec<&str> = Vec::new();
+        for node in std::iter::once(&self.transmitter).chain(self.transmitters.iter()) {
+            if node != "Vector__XXX" && !senders.contains(&node.as_str()) {
+                senders.push(node);
+            }
+        }
+        senders
+    }
+
+    pub fn tx_method(&self) -> &str {
+        &self.tx_method
+    }
+
+    pub fn attributes(&self) -> &HashMap<String, AttributeValue> {
+        &self.attributes
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Returns the number of bits the frame occupies on the wire, from SOF through EOF,
+    /// excluding dynamic stuff bits and the interframe space.
+    ///
+    /// Classic CAN: 44 bits for a standard ID (SOF, 11 bit ID, RTR, IDE, r0, 4 bit DLC, 15 bit CRC,
+    /// CRC delimiter, ACK slot and delimiter, 7 bit EOF), 64 bits for an extended ID (adding SRR,
+    /// the 18 bit ID extension and r1), plus `message_size * 8` payload bits.
+    ///
+    /// CAN FD: 22 (standard) or 41 (extended) bits up to the DLC including FDF, res, BRS and ESI,
+    /// the payload rounded up to the next valid FD length, 4 stuff-count bits, a 17 bit CRC with 6
+    /// fixed stuff bits (21 with 7 above 16 bytes), CRC delimiter, ACK and EOF.
+    ///
+    /// Messages marked FD by `VFrameFormat` or longer than 8 bytes are always counted as
+    /// CAN FD frames, since they cannot be sent as classic ones.
+    pub fn frame_bit_length(&self, fd: bool) -> u32 {
+        let payload_bytes = u32::try_from(self.message_size).unwrap_or(u32::MAX);
+        if !fd && !self.is_fd_frame() {
+            let header = match self.message_id {
+                MessageID::Standard(_) => 44,
+                MessageID::Extended(_) => 64,
+            };
+            return payload_bytes.saturating_mul(8).saturating_add(header);
+        }
+
+        let header = match self.message_id {
+            MessageID::Standard(_) => 22,
+            MessageID::Extended(_) => 41,
+        };
+        let payload_bytes = self.fd_payload_length() as u32;
+        let crc = if payload_bytes > 16 { 21 + 7 } else { 17 + 6 };
+        // Stuff count, CRC delimiter, ACK slot and delimiter, EOF
+        header + payload_bytes * 8 + 4 + crc + 1 + 2 + 7
+    }
+
+    /// Returns whether the message is sent as a CAN FD frame, i.e. marked FD or longer than 8 bytes
+    fn is_fd_frame(&self) -> bool {
+        self.is_fd || self.message_size > 8
+    }
+
+    /// Returns the payload length of the message as a CAN FD frame, rounded up to a valid FD length
+    fn fd_payload_length(&self) -> u64 {
+        FD_PAYLOAD_LENGTHS[dlc_for_length(self.message_size) as usize]
+    }
+
+    /// Removes the signal with the given name and returns it
+    pub fn remove_signal(&mut self, name: &str) -> Option<Signal> {
+        let pos = self.signals.iter().position(|sig| sig.name == name)?;
+        Some(self.signals.remove(pos))
+    }
+
+    /// Returns whether `nm_message` marks this as a network management message
+    pub fn is_network_management(&self) -> bool {
+        self.flag_attribute(self.nm_message, "NmMessage")
+    }
+
+    /// Returns whether any of `diag_request`, `diag_response` or `diag_state` marks this as a diagnostic message
+    pub fn is_diagnostic(&self) -> bool {
+        self.flag_attribute(self.diag_request, "DiagRequest")
+            || self.flag_attribute(self.diag_response, "DiagResponse")
+            || self.flag_attribute(self.diag_state, "DiagState")
+    }
+
+    /// Returns the flag resolved at parse time. A message built in code falls back to its
+    /// explicit attribute, where an enum value is taken by its index and any index but 0 means yes.
+    fn flag_attribute(&self, flag: Option<bool>, name: &str) -> bool {
+        flag.or_else(|| attribute_flag(&[], &self.attributes, name))
+            .unwrap_or(false)
+    }
+
+    /// Returns the signals ordered by the lowest payload bit they occupy (see [`Signal::bit_range`]),
+    /// so Motorola signals sort by their actual position rather than the raw `start_bit`
+    pub fn signals_by_start_bit(&self) -> Vec<&Signal> {
+        let mut signals: Vec<&Signal> = self.signals.iter().collect();
+        signals.sort_by_key(|sig| sig.bit_range().start);
+        signals
+    }
+
+    /// Returns the number of payload bits, [`Message::payload_len`] * 8. [`Message::bits_used`],
+    /// [`Message::unused_bits`] and [`Message::free_bits`] are bounded by it as well.
+    pub fn bits_available(&self) -> u64 {
+        self.payload_len() as u64 * 8
+    }
+
+    /// Returns the number of payload bits covered by at least one signal.
+    /// Multiplexed signals are counted against the multiplex group using the most bits.
+    pub fn bits_used(&self) -> u64 {
+        let available = self.bits_available();
+        let mut base = HashSet::new();
+        let mut groups: HashMap<u64, HashSet<u64>> = HashMap::new();
+
+        for sig in &self.signals {
+            let bits = match sig.multiplexer_type.switch_value() {
+                Some(value) => groups.entry(value).or_default(),
+                None => &mut base,
+            };
+            bits.extend(sig.occupied_bits().filter(|&bit| bit < available));
+        }
+
+        let largest_group = groups
+            .values()
+            .map(|group| group.difference(&base).count())
+            .max()
+            .unwrap_or(0);
+        (base.len() + largest_group) as u64
+    }
+
+    /// Returns the other signals touching any payload byte the given signal touches
+    /// (see [`Signal::byte_range`])
+    pub fn signals_in_same_bytes(&self, signal: &Signal) -> Vec<&Signal> {
+        let bytes = signal.byte_range();
+        self.signals
+            .iter()
+            .filter(|sig| sig.name != signal.name)
+            .filter(|sig| {
+                let other = sig.byte_range();
+                other.start() <= bytes.end() && bytes.start() <= other.end()
+            })
+            .collect()
+    }
+
+    /// Returns, for each payload byte and each bit within it (index 0 being the least significant bit),
+    /// the name of the signal occupying that cell. Where multiplexed signals share a bit the one
+    /// declared first is shown, and bits outside `message_size` are left out. The grid has
+    /// [`Message::payload_len`] rows.
+    pub fn layout_grid(&self) -> Vec<Vec<Option<&str>>> {
+        let mut grid = vec![vec![None; 8]; self.payload_len()];
+        for sig in &self.signals {
+            for bit in sig.occupied_bits() {
+                if let Some(cell) = grid
+                    .get_mut((bit / 8) as usize)
+                    .map(|byte| &mut byte[(bit % 8) as usize])
+                    && cell.is_none()
+                {
+                    *cell = Some(sig.name.as_str());
+                }
+            }
+        }
+        grid
+    }
+
+    /// Returns the contiguous payload bit ranges no signal covers, in `byte * 8 + bit` numbering.
+    /// A bit used by any multiplex case counts as used.
+    pub fn unused_bits(&self) -> Vec<Range<u64>> {
+        let available = self.bits_available();
+        let used: HashSet<u64> = self
+            .signals
+            .iter()
+            .flat_map(|sig| sig.occupied_bits())
+            .filter(|&bit| bit < available)
+            .collect();
+
+        let mut ranges: Vec<Range<u64>> = Vec::new();
+        for bit in (0..available).filter(|bit| !used.contains(bit)) {
+            match ranges.last_mut() {
+                Some(range) if range.end == bit => range.end += 1,
+                _ => ranges.push(bit..bit + 1),
+            }
+        }
+        ranges
+    }
+
+    /// Returns the number of payload bits not covered by any signal
+    pub fn free_bits(&self) -> u64 {
+        self.bits_available().saturating_sub(self.bits_used())
+    }
+
+    /// Returns every multiplexed signal paired with its switch value.
+    /// Plain signals and the top-level multiplexor are skipped.
+    pub fn multiplexed_signals(&self) -> impl Iterator<Item = (&Signal, u64)> {
+        self.signals.iter().filter_map(|sig| {
+            sig.multiplexer_type
+                .switch_value()
+                .map(|value| (sig, value))
+        })
+    }
+
+    /// Returns the value the top-level multiplexor (`M`) signal carries in the payload,
+    /// or `None` if the message has no multiplexor or the payload does not cover it
+    pub fn multiplexor_value(&self, data: &[u8]) -> Option<u64> {
+        self.signals
+            .iter()
+            .find(|sig| sig.multiplexer_type == MultiplexIndicator::Multiplexer)
+            .filter(|sig| sig.is_received(data))
+            .map(|sig| sig.extract_raw(data) as u64)
+    }
+
+    /// Returns whether a signal is present in the payload, i.e. all its bits were received and it is
+    /// not multiplexed or its (itself active) multiplexor signal carries the signal's switch value
+    fn is_signal_active(&self, signal: &Signal, data: &[u8]) -> bool {
+        self.is_signal_active_within(signal, data, self.signals.len())
+    }
+
+    /// `depth` bounds the walk up nested multiplexors, which malformed files may make cyclic
+    fn is_signal_active_within(&self, signal: &Signal, data: &[u8], depth: usize) -> bool {
+        if !signal.is_received(data) {
+            return false;
+        }
+        match signal.multiplexer_type.switch_value() {
+            Some(value) => signal
+                .multiplexor_signal
+                .as_deref()
+                .and_then(|name| self.signals.iter().find(|sig| sig.name == name))
+                .is_some_and(|switch| {
+                    depth > 0
+                        && self.is_signal_active_within(switch, data, depth - 1)
+                        && switch.extract_raw(data) as u64 == value
+                }),
+            None => true,
+        }
+    }
+
+    /// Yields every active signal in file order with its raw and physical value,
+    /// the pipeline shared by the `decode` methods
+    fn decoded_signals(&self, data: &[u8]) -> impl Iterator<Item = (&Signal, i64, f64)> {
+        self.signals
+            .iter()
+            .filter(move |sig| self.is_signal_active(sig, data))
+            .map(move |sig| {
+                let raw = sig.extract_raw(data);
+                (sig, raw, sig.raw_to_physical(raw))
+            })
+    }
+
+    /// Decodes every signal of the message from a frame payload.
+    /// The payload length is taken as the received DLC, so signals extending past it are left out,
+    /// and multiplexed signals are only included when their multiplexor carries their switch value.
+    pub fn decode(&self, data: &[u8]) -> HashMap<String, f64> {
+        self.decoded_signals(data)
+            .map(|(sig, _, physical)| (sig.name.clone(), physical))
+            .collect()
+    }
+
+    /// Decodes the message like [`Message::decode`], keeping the signals in file order
+    pub fn decode_ordered(&self, data: &[u8]) -> Vec<(String, f64)> {
+        self.decoded_signals(data)
+            .map(|(sig, _, physical)| (sig.name.clone(), physical))
+            .collect()
+    }
+
+    /// Decodes every active signal like [`Message::decode`] into its raw and physical value,
+    /// value description and unit, keeping the signals in file order
+    pub fn decode_full(&self, data: &[u8]) -> Vec<DecodedSignal> {
+        self.decoded_signals(data)
+            .map(|(sig, raw, physical)| DecodedSignal {
+                name: sig.name.clone(),
+                raw,
+                physical,
+                description: sig.describe(raw).map(str::to_string),
+                unit: sig.unit.clone(),
+            })
+            .collect()
+    }
+
+    /// Decodes the message like [`Message::decode`] with the given options applied
+    pub fn decode_with(&self, data: &[u8], options: &DecodeOptions) -> HashMap<String, f64> {
+        let Some(byte_order) = options.force_byte_order else {
+            return self.decode(data);
+        };
+        let mut forced = self.clone();
+        for sig in forced.signals.iter_mut() {
+            sig.byte_order = byte_order;
+        }
+        forced.decode(data)
+    }
+
+    /// Decodes the sign-corrected raw value of every active signal without applying factor and offset
+    pub fn decode_raw(&self, data: &[u8]) -> HashMap<String, i64> {
+        self.decoded_signals(data)
+            .map(|(sig, raw, _)| (sig.name.clone(), raw))
+            .collect()
+    }
+
+    /// Decodes the message like [`Message::decode`], but fails instead of leaving signals out
+    /// when the payload is too short or the multiplex state cannot be resolved
+    pub fn try_decode(&self, data: &[u8]) -> Result<HashMap<String, f64>, DecodeError> {
+        let too_short = |sig: &Signal| DecodeError::FrameTooShort {
+            signal: sig.name.clone(),
+            required: sig.required_bytes(),
+            received: data.len(),
+        };
+
+        for sig in &self.signals {
+            let Some(value) = sig.multiplexer_type.switch_value() else {
+                if !sig.is_received(data) {
+                    return Err(too_short(sig));
+                }
+                continue;
+            };
+            let multiplexor = sig.multiplexor_signal.as_deref().unwrap_or_default();
+            let Some(switch) = self.signals.iter().find(|s| s.name == multiplexor) else {
+                return Err(DecodeError::UnknownMultiplexor {
+                    signal: sig.name.clone(),
+                    multiplexor: multiplexor.to_string(),
+                });
+            };
+            if self.is_signal_active(switch, data)
+                && switch.extract_raw(data) as u64 == value
+                && !sig.is_received(data)
+            {
+                return Err(too_short(sig));
+            }
+        }
+
+        for switch in self
+            .signals
+            .iter()
+            .filter(|sig| sig.is_multiplexor() && self.is_signal_active(sig, data))
+        {
+            let value = switch.extract_raw(data) as u64;
+            let mut switched = self
+                .multiplexed_signals()
+                .filter(|(sig, _)| sig.multiplexor_signal.as_deref() == Some(switch.name.as_str()))
+                .peekable();
+            if switched.peek().is_some() && !switched.any(|(_, v)| v == value) {
+                return Err(DecodeError::InactiveMultiplex {
+                    multiplexor: switch.name.clone(),
+                    value,
+                });
+            }
+        }
+
+        Ok(self.decode(data))
+    }
+
+    /// Encodes a frame payload of [`Message::payload_len`] bytes from physical values keyed by signal name.
+    /// Signals without a value are left as zero bits, as are names the message does not contain.
+    pub fn encode(&self, values: &HashMap<String, f64>) -> Vec<u8> {
+        let mut data = vec![0u8; self.payload_len()];
+        for sig in &self.signals {
+            if let Some(&value) = values.get(&sig.name) {
+                sig.encode(value, &mut data);
+            }
+        }
+        data
+    }
+
+    /// Encodes the values like [`Message::encode`], but fails when two of the encoded signals
+    /// share a bit, which would leave the frame with corrupted values
+    pub fn try_encode(&self, values: &HashMap<String, f64>) -> Result<Vec<u8>, DecodeError> {
+        let mut data = vec![0u8; self.payload_len()];
+        let mut owners: HashMap<u64, &str> = HashMap::new();
+        for sig in &self.signals {
+            let Some(&value) = values.get(&sig.name) else {
+                continue;
+            };
+            for bit in sig.occupied_bits() {
+                if let Some(owner) = owners.insert(bit, &sig.name) {
+                    return Err(DecodeError::Overlap {
+                        a: owner.to_string(),
+                        b: sig.name.clone(),
+                    });
+                }
+            }
+            sig.encode(value, &mut data);
+        }
+        Ok(data)
+    }
+}
+
+/// A signal value as produced by [`Message::decode_full`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedSignal {
+    pub name: String,
+    /// Sign-corrected raw value, or the bits of an IEEE signal
+    pub raw: i64,
+    pub physical: f64,
+    /// `VAL_` description of the raw value
+    pub description: Option<String>,
+    pub unit: String,
+}
+
+/// Options for [`Message::decode_with`]. The default decodes every signal as declared.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DecodeOptions {
+    /// Decodes every signal with this byte order instead of the declared one, keeping the declared
+    /// start bit. A diagnostic aid for databases suspected to have a mislabeled byte order.
+    pub force_byte_order: Option<ByteOrder>,
+}
+
+/// A reason [`Message::try_decode`] rejected a frame or [`Message::try_encode`] rejected values
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodeError {
+    /// The payload ends before the last byte of a signal
+    FrameTooShort {
+        signal: String,
+        required: usize,
+        received: usize,
+    },
+    /// The multiplexor carries a value no multiplexed signal is defined for
+    InactiveMultiplex { multiplexor: String, value: u64 },
+    /// A multiplexed signal refers to a multiplexor the message does not contain
+    UnknownMultiplexor { signal: String, multiplexor: String },
+    /// Two encoded signals write to the same bits
+    Overlap { a: String, b: String },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::FrameTooShort {
+                signal,
+                required,
+                received,
+            } => write!(
+                f,
+                "Signal {} needs {} bytes but the frame has {}",
+                signal, required, received
+            ),
+            DecodeError::InactiveMultiplex { multiplexor, value } => write!(
+                f,
+                "Multiplexor {} carries {} which selects no signal",
+                multiplexor, value
+            ),
+            DecodeError::UnknownMultiplexor {
+                signal,
+                multiplexor,
+            } => write!(
+                f,
+                "Signal {} is switched by unknown multiplexor {}",
+                signal, multiplexor
+            ),
+            DecodeError::Overlap { a, b } => {
+                write!(f, "Signals {} and {} overlap", a, b)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// A type decoded from and encoded to the payload of one message with a layout known at compile time.
+/// Complements the `HashMap` based [`Message::decode`] and [`Message::encode`] for dynamic use;
+/// see [`impl_frame_codec!`] to implement it for a struct of `f64` fields.
+pub trait FrameCodec: Sized {
+    fn decode(data: &[u8]) -> Self;
+    fn encode(&self) -> Vec<u8>;
+}
+
+/// Implements [`FrameCodec`] for a struct of `f64` fields, each mapped to a signal of a message.
+///
+/// The message expression is evaluated on every call and must yield a `&Message`, e.g. from a
+/// function returning a lazily parsed database entry. Fields whose signal the message does not
+/// contain decode as 0 and are not encoded.
+///
+/// ```rust
+/// use rs_dbc::{impl_frame_codec, FrameCodec, Message, MessageID, Signal};
+/// use std::sync::OnceLock;
+///
+/// fn engine() -> &'static Message {
+///     static ENGINE: OnceLock<Message> = OnceLock::new();
+///     ENGINE.get_or_init(|| {
+///         let mut msg = Message::new("Engine", MessageID::Standard(0x100), 2);
+///         msg.signals.push(Signal::new("Speed", 0, 16));
+///         msg
+///     })
+/// }
+///
+/// struct Engine {
+///     speed: f64,
+/// }
+///
+/// impl_frame_codec!(Engine, engine(), { speed: "Speed" });
+///
+/// let frame = Engine { speed: 1200.0 }.encode();
+/// assert_eq!(Engine::decode(&frame).speed, 1200.0);
+/// ```
+#[macro_export]
+macro_rules! impl_frame_codec {
+    ($ty:ty, $message:expr, { $($field:ident : $signal:expr),* $(,)? }) => {
+        impl $crate::FrameCodec for $ty {
+            fn decode(data: &[u8]) -> Self {
+                let message: &$crate::Message = $message;
+                Self {
+                    $($field: message
+                        .signals
+                        .iter()
+                        .find(|sig| sig.name == $signal)
+                        .map_or(0.0, |sig| sig.decode(data)),)*
+                }
+            }
+
+            fn encode(&self) -> Vec<u8> {
+                let message: &$crate::Message = $message;
+                let mut data = vec![0u8; message.payload_len()];
+                $(if let Some(sig) = message.signals.iter().find(|sig| sig.name == $signal) {
+                    sig.encode(self.$field, &mut data);
+                })*
+                data
+            }
+        }
+    };
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    pub name: String,
+    pub comment: Option<String>,
+    /// Explicit `BA_` values assigned to this node
+    pub attributes: HashMap<String, AttributeValue>,
+}
+
+impl Node {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    pub fn attributes(&self) -> &HashMap<String, AttributeValue> {
+        &self.attributes
+    }
+}
+
+/// A global `VAL_TABLE_` of value descriptions shared by name
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValueTable {
+    pub name: String,
+    /// Descriptions keyed by raw value, as in `Signal::value_descriptions`
+    pub values: HashMap<i64, String>,
+}
+
+impl ValueTable {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn values(&self) -> &HashMap<i64, String> {
+        &self.values
+    }
+}
+
+/// A `SIG_GROUP_` bundling signals of one message
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignalGroup {
+    pub message_id: MessageID,
+    pub name: String,
+    pub repetitions: u32,
+    /// Names of the member signals
+    pub signals: Vec<String>,
+}
+
+impl SignalGroup {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn signals(&self) -> &Vec<String> {
+        &self.signals
+    }
+
+    /// Returns the union of the receivers of all member signals found in the given message
+    pub fn receivers(&self, message: &Message) -> BTreeSet<String> {
+        message
+            .signals
+            .iter()
+            .filter(|sig| self.signals.contains(&sig.name))
+            .flat_map(|sig| sig.receivers.iter().cloned())
+            .collect()
+    }
+}
+
+/// Environment variable value type, the `env_var_type` of an `EV_` line
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnvVarType {
+    Integer,
+    Float,
+    String,
+}
+
+/// Access type encoded in the `DUMMY_NODE_VECTORn` placeholder of an `EV_` line
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessType {
+    Unrestricted,
+    Read,
+    Write,
+    ReadWrite,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnvVar {
+    pub name: String,
+    pub var_type: EnvVarType,
+    pub min: f64,
+    pub max: f64,
+    pub unit: String,
+    pub initial_value: f64,
+    pub ev_id: u64,
+    pub access_type: AccessType,
+    /// Nodes allowed to access the variable, without `Vector__XXX`
+    pub access_nodes: Vec<String>,
+    /// Byte size declared in `ENVVAR_DATA_`
+    pub data_size: Option<u32>,
+    /// Explicit `BA_` values assigned to this environment variable
+    pub attributes: HashMap<String, AttributeValue>,
+}
+
+impl EnvVar {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn var_type(&self) -> EnvVarType {
+        self.var_type
+    }
+
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    pub fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    pub fn initial_value(&self) -> f64 {
+        self.initial_value
+    }
+
+    pub fn ev_id(&self) -> u64 {
+        self.ev_id
+    }
+
+    pub fn access_type(&self) -> AccessType {
+        self.access_type
+    }
+
+    pub fn access_nodes(&self) -> &Vec<String> {
+        &self.access_nodes
+    }
+
+    pub fn data_size(&self) -> Option<u32> {
+        self.data_size
+    }
+
+    pub fn attributes(&self) -> &HashMap<String, AttributeValue> {
+        &self.attributes
+    }
+}
+
+/// A malformed construct accepted by [`Dbc::parse_with_warnings`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseWarning {
+    /// The multiplex indicator of a signal is none of `M`, `m<n>` or `m<n>M`; the signal was parsed as plain
+    UnknownMultiplexIndicator {
+        message_id: u32,
+        signal: String,
+        token: String,
+    },
+    /// A signal appeared outside a `BO_` block, e.g. in a reordered merge,
+    /// and was attached to the next message, or the last one if none follows
+    ReorderedSignal { message_id: u32, signal: String },
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseWarning::UnknownMultiplexIndicator {
+                message_id,
+                signal,
+                token,
+            } => write!(
+                f,
+                "Signal {} in message {} has unrecognized multiplex indicator {}, treated as plain",
+                signal, message_id, token
+            ),
+            ParseWarning::ReorderedSignal { message_id, signal } => write!(
+                f,
+                "Signal {} is outside a message block and was attached to message {}",
+                signal, message_id
+            ),
+        }
+    }
+}
+
+/// How serious a [`ValidationWarning`] is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// The database cannot be used as is, e.g. for decoding
+    Error,
+    /// The database is usable but likely not what its author intended
+    Warning,
+}
+
+/// A problem found by [`Dbc::validate`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationWarning {
+    /// The message transmitter is not listed in `BU_`
+    UnknownTransmitter {
+        message: String,
+        transmitter: String,
+    },
+    /// A signal receiver is not listed in `BU_`
+    UnknownReceiver {
+        message: String,
+        signal: String,
+        receiver: String,
+    },
+    /// A signal is zero bits wide or wider than the 64-bit raw value model
+    InvalidSignalSize {
+        message: String,
+        signal: String,
+        signal_size: u64,
+    },
+    /// A signal has a factor of 0, so every raw value decodes to the offset
+    ZeroFactor { message: String, signal: String },
+}
+
+impl ValidationWarning {
+    /// Invalid signal sizes are errors, everything else is a warning
+    pub fn severity(&self) -> Severity {
+        match self {
+            ValidationWarning::InvalidSignalSize { .. } => Severity::Error,
+            ValidationWarning::UnknownTransmitter { .. }
+            | ValidationWarning::UnknownReceiver { .. }
+            | ValidationWarning::ZeroFactor { .. } => Severity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationWarning::UnknownTransmitter {
+                message,
+                transmitter,
+            } => write!(
+                f,
+                "Message {} is sent by unknown node {}",
+                message, transmitter
+            ),
+            ValidationWarning::UnknownReceiver {
+                message,
+                signal,
+                receiver,
+            } => write!(
+                f,
+                "Signal {} in message {} is received by unknown node {}",
+                signal, message, receiver
+            ),
+            ValidationWarning::InvalidSignalSize {
+                message,
+                signal,
+                signal_size,
+            } => write!(
+                f,
+                "Signal {} in message {} has an invalid size of {} bits",
+                signal, message, signal_size
+            ),
+            ValidationWarning::ZeroFactor { message, signal } => write!(
+                f,
+                "Signal {} in message {} has a factor of 0",
+                signal, message
+            ),
+        }
+    }
+}
+
+/// Item counts of a database, see [`Dbc::summary`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DbcSummary {
+    pub standard_messages: usize,
+    pub extended_messages: usize,
+    pub signals: usize,
+    /// Messages with a multiplexor signal
+    pub multiplexed_messages: usize,
+    pub nodes: usize,
+}
+
+impl DbcSummary {
+    /// Returns the number of standard and extended messages
+    pub fn messages(&self) -> usize {
+        self.standard_messages + self.extended_messages
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dbc {
+    /// Empty when the file has no `VERSION` line
+    pub version: String,
+    pub messages: Vec<Message>,
+    /// Empty when the file has no `BU_` line; messages still parse without any header
+    pub nodes: Vec<Node>,
+    pub attribute_definitions: Vec<AttributeDefinition>,
+    pub env_vars: Vec<EnvVar>,
+    pub relation_attribute_definitions: Vec<RelationAttributeDefinition>,
+    pub relation_attributes: Vec<RelationAttribute>,
+    pub signal_groups: Vec<SignalGroup>,
+    pub value_tables: Vec<ValueTable>,
+    /// Explicit network-level `BA_` values, such as `DBName` or `BusType`
+    pub attributes: HashMap<String, AttributeValue>,
+    /// Statements the parser does not model, kept verbatim so `to_dbc_string` can re-emit them.
+    /// They are opaque, and only their relative order is preserved, not their position in the file.
+    pub raw_unparsed: Vec<String>,
+}
+
+impl Dbc {
+    pub fn from_slice(buffer: &[u8]) -> Result<Dbc, Error> {
+        let dbc_input = str::from_utf8(buffer).map_err(Error::Utf8)?;
+        Self::try_from(dbc_input)
+    }
+
+    pub fn from_slice_lossy(buffer: &[u8]) -> Result<Dbc, Error> {
+        let dbc_input = String::from_utf8_lossy(buffer);
+        Self::try_from(dbc_input.as_ref())
+    }
+
+    /// Decompresses a gzip stream, e.g. a `.dbc.gz` file, and parses the contained DBC
+    #[cfg(feature = "gzip")]
+    pub fn from_gzip_reader<R: std::io::Read>(reader: R) -> Result<Dbc, Error> {
+        use std::io::Read;
+
+        let mut buffer = Vec::new();
+        flate2::read::GzDecoder::new(reader)
+            .read_to_end(&mut buffer)
+            .map_err(Error::Io)?;
+        Self::from_slice(&buffer)
+    }
+
+    pub fn message_by_id(&self, id: MessageID) -> Option<&Message> {
+        self.messages.iter().find(|msg| msg.message_id == id)
+    }
+
+    /// Removes the message with the given ID and returns it
+    pub fn remove_message(&mut self, id: MessageID) -> Option<Message> {
+        let pos = self.messages.iter().position(|msg| msg.message_id == id)?;
+        Some(self.messages.remove(pos))
+    }
+
+    /// Returns the message for a hardware-style frame ID (e.g. from socketcan),
+    /// where bit 31 flags an extended frame and the remaining bits are masked to 11 or 29 bits
+    pub fn resolve_id(&self, raw: u32) -> Option<&Message> {
+        let id = if raw & (1 << 31) != 0 {
+            MessageID::Extended(raw & 0x1FFF_FFFF)
+        } else {
+            MessageID::Standard((raw & 0x7FF) as u16)
+        };
+        self.message_by_id(id)
+    }
+
+    /// Returns every message whose name matches ignoring case.
+    /// DBC names are case sensitive, so this is a convenience for interactive lookups and may match several messages.
+    pub fn message_by_name_ci(&self, name: &str) -> Vec<&Message> {
+        let name = name.to_lowercase();
+        self.messages
+            .iter()
+            .filter(|msg| msg.message_name.to_lowercase() == name)
+            .collect()
+    }
+
+    /// Returns every signal whose name matches ignoring case, together with its message.
+    /// Like `message_by_name_ci` this may match several signals, also across messages.
+    pub fn signal_by_name_ci(&self, name: &str) -> Vec<(&Message, &Signal)> {
+        let name = name.to_lowercase();
+        self.messages
+            .iter()
+            .flat_map(|msg| msg.signals.iter().map(move |sig| (msg, sig)))
+            .filter(|(_, sig)| sig.name.to_lowercase() == name)
+            .collect()
+    }
+
+    /// Returns the `BA_` value of a message attribute, falling back to its `BA_DEF_DEF_` default
+    pub fn message_attribute<'a>(
+        &'a self,
+        msg: &'a Message,
+        name: &str,
+    ) -> Option<&'a AttributeValue> {
+        msg.attributes
+            .get(name)
+            .or_else(|| attribute_default(&self.attribute_definitions, name))
+    }
+
+    /// Returns the `BA_` value of a signal attribute, falling back to its `BA_DEF_DEF_` default
+    pub fn signal_attribute<'a>(
+        &'a self,
+        sig: &'a Signal,
+        name: &str,
+    ) -> Option<&'a AttributeValue> {
+        sig.attributes
+            .get(name)
+            .or_else(|| attribute_default(&self.attribute_definitions, name))
+    }
+
+    /// Returns the messages sorted by [`MessageID::raw`]. Extended IDs carry bit 31 there,
+    /// so all standard messages come first, followed by the extended ones in ID order.
+    pub fn messages_sorted_by_id(&self) -> Vec<&Message> {
+        let mut messages: Vec<&Message> = self.messages.iter().collect();
+        messages.sort_by_key(|msg| msg.message_id.raw());
+        messages
+    }
+
+    /// Returns the messages with an 11 bit standard ID
+    pub fn standard_messages(&self) -> impl Iterator<Item = &Message> {
+        self.messages
+            .iter()
+            .filter(|msg| matches!(msg.message_id, MessageID::Standard(_)))
+    }
+
+    /// Returns the messages with a 29 bit extended ID
+    pub fn extended_messages(&self) -> impl Iterator<Item = &Message> {
+        self.messages
+            .iter()
+            .filter(|msg| matches!(msg.message_id, MessageID::Extended(_)))
+    }
+
+    /// Returns every message that has a signal with the given name
+    pub fn messages_containing_signal(&self, signal_name: &str) -> Vec<&Message> {
+        self.messages
+            .iter()
+            .filter(|msg| msg.signals.iter().any(|sig| sig.name == signal_name))
+            .collect()
+    }
+
+    /// Yields every signal together with its message
+    pub fn signals(&self) -> impl Iterator<Item = (&Message, &Signal)> {
+        self.messages
+            .iter()
+            .flat_map(|msg| msg.signals.iter().map(move |sig| (msg, sig)))
+    }
+
+    /// Returns the descriptions of the `VAL_TABLE_` with the given name
+    pub fn value_table(&self, name: &str) -> Option<&HashMap<i64, String>> {
+        self.value_tables
+            .iter()
+            .find(|table| table.name == name)
+            .map(|table| &table.values)
+    }
+
+    /// Returns the signals using the `VAL_TABLE_` with the given name. DBC files carry no
+    /// reference from a signal to its table, so these are the signals whose `VAL_` descriptions
+    /// equal the table, which is how tools copy a table onto its signals.
+    pub fn signals_using_table(&self, name: &str) -> Vec<(&Message, &Signal)> {
+        let Some(values) = self.value_table(name).filter(|values| !values.is_empty()) else {
+            return Vec::new();
+        };
+        self.signals()
+            .filter(|(_, sig)| sig.value_descriptions == *values)
+            .collect()
+    }
+
+    /// Returns the ID of the message owning the signal. The signal must be borrowed from this
+    /// database, as it is found by address rather than by name, which may be shared by messages.
+    pub fn message_id_of(&self, signal: &Signal) -> Option<MessageID> {
+        self.signals()
+            .find(|(_, sig)| std::ptr::eq(*sig, signal))
+            .map(|(msg, _)| msg.message_id)
+    }
+
+    /// Consumes the database and yields every signal together with the ID of its message
+    pub fn into_signals(self) -> impl Iterator<Item = (MessageID, Signal)> {
+        self.messages.into_iter().flat_map(|msg| {
+            let id = msg.message_id;
+            msg.signals.into_iter().map(move |sig| (id, sig))
+        })
+    }
+
+    /// Returns the messages sent with exactly the given cycle time in ms.
+    /// A cycle time of 0 selects the messages without one, typically event-driven messages.
+    pub fn messages_with_cycle_time(&self, ms: u32) -> Vec<&Message> {
+        self.messages
+            .iter()
+            .filter(|msg| msg.cycle_time == ms)
+            .collect()
+    }
+
+    /// Returns the messages whose cycle time lies within `min..=max` ms.
+    /// Pass a `min` of at least 1 to leave out messages without a cycle time.
+    pub fn messages_in_cycle_range(&self, min: u32, max: u32) -> Vec<&Message> {
+        self.messages
+            .iter()
+            .filter(|msg| (min..=max).contains(&msg.cycle_time))
+            .collect()
+    }
+
+    /// Estimates the fraction of the bus taken up by cyclic messages at the given bitrate.
+    /// Each frame is counted as a classic CAN data frame with worst-case bit stuffing, i.e. one stuff bit
+    /// per four bits of the 34 (standard) or 54 (extended) stuffable header bits plus the payload.
+    /// CAN FD frames (see [`Message::frame_bit_length`]) are stuffed the same way over their 22 or 41
+    /// header bits and rounded-up payload, their CRC carrying fixed stuff bits. They are counted at
+    /// the nominal bitrate throughout, so for frames using bit rate switching the estimate is an
+    /// upper bound. Messages without a cycle time are ignored; a result above 1.0 means the bus is overloaded.
+    pub fn estimated_bus_load(&self, bitrate: u32) -> f64 {
+        if bitrate == 0 {
+            return 0.0;
+        }
+
+        let bits_per_second: f64 = self
+            .messages
+            .iter()
+            .filter(|msg| msg.cycle_time > 0)
+            .map(|msg| {
+                let fd = msg.is_fd_frame();
+                let (stuffable, payload): (u64, u64) = match (fd, msg.message_id) {
+                    (false, MessageID::Standard(_)) => (34, msg.message_size),
+                    (false, MessageID::Extended(_)) => (54, msg.message_size),
+                    (true, MessageID::Standard(_)) => (22, msg.fd_payload_length()),
+                    (true, MessageID::Extended(_)) => (41, msg.fd_payload_length()),
+                };
+                let stuff_bits = (stuffable.saturating_add(payload.saturating_mul(8)) - 1) / 4;
+                // Plus the 3 bit interframe space
+                let frame_bits = msg.frame_bit_length(fd) as u64 + 3 + stuff_bits;
+                frame_bits as f64 * 1000.0 / msg.cycle_time as f64
+            })
+            .sum();
+
+        bits_per_second / bitrate as f64
+    }
+
+    /// Rewrites the unit of every signal: units found in `map` are replaced by its value,
+    /// any others by [`Signal::normalized_unit`]. Returns the number of changed units.
+    pub fn normalize_units(&mut self, map: &HashMap<String, String>) -> usize {
+        let mut count = 0;
+        for sig in self
+            .messages
+            .iter_mut()
+            .flat_map(|msg| msg.signals.iter_mut())
+        {
+            let unit = map
+                .get(sig.unit.trim())
+                .cloned()
+                .unwrap_or_else(|| sig.normalized_unit());
+            if unit != sig.unit {
+                sig.unit = unit;
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Returns the distinct non-empty units used by any signal
+    pub fn units(&self) -> BTreeSet<String> {
+        self.messages
+            .iter()
+            .flat_map(|msg| msg.signals.iter())
+            .filter(|sig| !sig.unit.is_empty())
+            .map(|sig| sig.unit.clone())
+            .collect()
+    }
+
+    pub fn message_count(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns the number of signals summed across all messages
+    pub fn signal_count(&self) -> usize {
+        self.messages.iter().map(Message::signal_count).sum()
+    }
+
+    /// Counts the messages by ID kind, the signals, multiplexed messages and nodes
+    pub fn summary(&self) -> DbcSummary {
+        let mut summary = DbcSummary {
+            nodes: self.nodes.len(),
+            ..DbcSummary::default()
+        };
+        for msg in &self.messages {
+            match msg.message_id {
+                MessageID::Standard(_) => summary.standard_messages += 1,
+                MessageID::Extended(_) => summary.extended_messages += 1,
+            }
+            summary.signals += msg.signal_count();
+            if msg.signals.iter().any(Signal::is_multiplexor) {
+                summary.multiplexed_messages += 1;
+            }
+        }
+        summary
+    }
+
+    /// Returns the distinct nodes sending at least one message, as `BO_` transmitter
+    /// or in `BO_TX_BU_`, excluding `Vector__XXX`
+    pub fn transmitters(&self) -> BTreeSet<String> {
+        self.messages
+            .iter()
+            .flat_map(|msg| std::iter::once(&msg.transmitter).chain(msg.transmitters.iter()))
+            .filter(|node| node.as_str() != "Vector__XXX")
+            .cloned()
+            .collect()
+    }
+
+    /// Returns every signal with value descriptions together with its message
+    pub fn enumerated_signals(&self) -> Vec<(&Message, &Signal)> {
+        self.messages
+            .iter()
+            .flat_map(|msg| msg.signals.iter().map(move |sig| (msg, sig)))
+            .filter(|(_, sig)| !sig.value_descriptions.is_empty())
+            .collect()
+    }
+
+    /// Returns every signal received by the given node together with its message
+    pub fn signals_received_by(&self, node: &str) -> Vec<(&Message, &Signal)> {
+        self.messages
+            .iter()
+            .flat_map(|msg| msg.signals.iter().map(move |sig| (msg, sig)))
+            .filter(|(_, sig)| sig.receivers.iter().any(|receiver| receiver == node))
+            .collect()
+    }
+
+    /// Maps every receiving node to the (message ID, signal name) pairs it consumes,
+    /// in file order. Equivalent to [`Dbc::signals_received_by`] for all nodes at once.
+    pub fn routing_table(&self) -> HashMap<String, Vec<(MessageID, String)>> {
+        let mut table: HashMap<String, Vec<(MessageID, String)>> = HashMap::new();
+        for msg in &self.messages {
+            for sig in &msg.signals {
+                for receiver in &sig.receivers {
+                    table
+                        .entry(receiver.clone())
+                        .or_default()
+                        .push((msg.message_id, sig.name.clone()));
+                }
+            }
+        }
+        table
+    }
+
+    /// Changes the ID of a message together with the `SIG_GROUP_` and `BA_REL_` entries referring
+    /// to it. Comments, attributes, value descriptions and initial values are stored on the
+    /// message and its signals, so they follow along; statements in `raw_unparsed` are not updated.
+    /// Fails without changing anything when `old` does not exist or `new` is already taken.
+    pub fn renumber_message(&mut self, old: MessageID, new: MessageID) -> Result<(), Error> {
+        if old == new && self.message_by_id(old).is_some() {
+            return Ok(());
+        }
+        if self.message_by_id(new).is_some() {
+            return Err(Error::DuplicateMessage(new));
+        }
+        let Some(msg) = self.messages.iter_mut().find(|msg| msg.message_id == old) else {
+            return Err(Error::UnknownMessage(old));
+        };
+        msg.message_id = new;
+
+        for group in self.signal_groups.iter_mut() {
+            if group.message_id == old {
+                group.message_id = new;
+            }
+        }
+        for attr in self.relation_attributes.iter_mut() {
+            match &mut attr.target {
+                RelationTarget::Message(id) | RelationTarget::Signal(id, _) if *id == old => {
+                    *id = new;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Renames a node in `BU_`, message transmitters, `BO_TX_BU_` lists, signal receivers,
+    /// environment variable access lists and `BA_REL_` entries.
+    /// Returns the number of updated references. `Vector__XXX` is never renamed.
+    pub fn rename_node(&mut self, old: &str, new: &str) -> usize {
+        if old == "Vector__XXX" {
+            return 0;
+        }
+
+        let mut count = 0;
+        let mut rename = |name: &mut String| {
+            if name == old {
+                *name = new.to_string();
+                count += 1;
+            }
+        };
+
+        for node in self.nodes.iter_mut() {
+            rename(&mut node.name);
+        }
+        for msg in self.messages.iter_mut() {
+            rename(&mut msg.transmitter);
+            msg.transmitters.iter_mut().for_each(&mut rename);
+            for sig in msg.signals.iter_mut() {
+                sig.receivers.iter_mut().for_each(&mut rename);
+            }
+        }
+        for env_var in self.env_vars.iter_mut() {
+            env_var.access_nodes.iter_mut().for_each(&mut rename);
+        }
+        for attr in self.relation_attributes.iter_mut() {
+            rename(&mut attr.node);
+        }
+
+        count
+    }
+
+    /// Cross-checks message transmitters and signal receivers against the `BU_` node list
+    /// (the `Vector__XXX` placeholder is never reported) and flags signals of zero or more than 64 bits
+    /// as well as signals with a factor of 0.
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        let is_known =
+            |name: &str| name == "Vector__XXX" || self.nodes.iter().any(|node| node.name == name);
+        let mut warnings = Vec::new();
+
+        for msg in &self.messages {
+            if !is_known(&msg.transmitter) {
+                warnings.push(ValidationWarning::UnknownTransmitter {
+                    message: msg.message_name.clone(),
+                    transmitter: msg.transmitter.clone(),
+                });
+            }
+
+            for sig in &msg.signals {
+                if sig.signal_size == 0 || sig.signal_size > 64 {
+                    warnings.push(ValidationWarning::InvalidSignalSize {
+                        message: msg.message_name.clone(),
+                        signal: sig.name.clone(),
+                        signal_size: sig.signal_size,
+                    });
+                }
+                if sig.factor == 0.0 {
+                    warnings.push(ValidationWarning::ZeroFactor {
+                        message: msg.message_name.clone(),
+                        signal: sig.name.clone(),
+                    });
+                }
+
+                for receiver in sig.receivers.iter().filter(|r| !is_known(r)) {
+                    warnings.push(ValidationWarning::UnknownReceiver {
+                        message: msg.message_name.clone(),
+                        signal: sig.name.clone(),
+                        receiver: receiver.clone(),
+                    });
+                }
+            }
+        }
+
+        warnings
+    }
+
+    /// Adds the messages, nodes, attribute definitions, environment variables, relation
+    /// attributes and signal groups of `other` that this database does not have yet.
+    /// Entries present in both are kept from `self`; the ones that differ are returned as conflicts.
+    pub fn merge(&mut self, other: Dbc) -> Vec<MergeConflict> {
+        let mut conflicts = Vec::new();
+
+        if self.version.is_empty() {
+            self.version = other.version;
+        }
+        for msg in other.messages {
+            match self.message_by_id(msg.message_id) {
+                Some(existing) if *existing != msg => {
+                    conflicts.push(MergeConflict::Message(msg.message_id))
+                }
+                Some(_) => {}
+                None => self.messages.push(msg),
+            }
+        }
+        for node in other.nodes {
+            match self.nodes.iter().find(|n| n.name == node.name) {
+                Some(existing) if *existing != node => {
+                    conflicts.push(MergeConflict::Node(node.name))
+                }
+                Some(_) => {}
+                None => self.nodes.push(node),
+            }
+        }
+        for def in other.attribute_definitions {
+            match self
+                .attribute_definitions
+                .iter()
+                .find(|d| d.name == def.name)
+            {
+                Some(existing) if *existing != def => {
+                    conflicts.push(MergeConflict::AttributeDefinition(def.name))
+                }
+                Some(_) => {}
+                None => self.attribute_definitions.push(def),
+            }
+        }
+        for env_var in other.env_vars {
+            match self.env_vars.iter().find(|e| e.name == env_var.name) {
+                Some(existing) if *existing != env_var => {
+                    conflicts.push(MergeConflict::EnvVar(env_var.name))
+                }
+                Some(_) => {}
+                None => self.env_vars.push(env_var),
+            }
+        }
+        for def in other.relation_attribute_definitions {
+            match self
+                .relation_attribute_definitions
+                .iter()
+                .find(|d| d.name == def.name)
+            {
+                Some(existing) if *existing != def => {
+                    conflicts.push(MergeConflict::AttributeDefinition(def.name))
+                }
+                Some(_) => {}
+                None => self.relation_attribute_definitions.push(def),
+            }
+        }
+        for attr in other.relation_attributes {
+            if !self.relation_attributes.contains(&attr) {
+                self.relation_attributes.push(attr);
+            }
+        }
+        for group in other.signal_groups {
+            if !self.signal_groups.contains(&group) {
+                self.signal_groups.push(group);
+            }
+        }
+        for (name, value) in other.attributes {
+            self.attributes.entry(name).or_insert(value);
+        }
+        for table in other.value_tables {
+            match self.value_tables.iter().find(|t| t.name == table.name) {
+                Some(existing) if *existing != table => {
+                    conflicts.push(MergeConflict::ValueTable(table.name))
+                }
+                Some(_) => {}
+                None => self.value_tables.push(table),
+            }
+        }
+        for statement in other.raw_unparsed {
+            if !self.raw_unparsed.contains(&statement) {
+                self.raw_unparsed.push(statement);
+            }
+        }
+
+        conflicts
+    }
+
+    /// Parses several DBC files and merges them in the given order with [`Dbc::merge`].
+    /// Conflicting definitions fail with [`Error::Conflicts`], which still carries the merged database.
+    pub fn from_files<P: AsRef<std::path::Path>>(paths: &[P]) -> Result<Dbc, Error> {
+        let mut merged: Option<Dbc> = None;
+        let mut conflicts = Vec::new();
+
+        for path in paths {
+            let buffer = std::fs::read(path).map_err(Error::Io)?;
+            let dbc = Dbc::from_slice(&buffer)?;
+            match merged.as_mut() {
+                Some(merged) => conflicts.extend(merged.merge(dbc)),
+                None => merged = Some(dbc),
+            }
+        }
+
+        let merged = merged.ok_or_else(|| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "no DBC files given",
+            ))
+        })?;
+        if !conflicts.is_empty() {
+            return Err(Error::Conflicts(Box::new(merged), conflicts));
+        }
+        Ok(merged)
+    }
+}
+
+/// A definition that two merged databases disagree on, see [`Dbc::merge`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum MergeConflict {
+    Message(MessageID),
+    Node(String),
+    /// A `BA_DEF_` or `BA_DEF_REL_` definition
+    AttributeDefinition(String),
+    EnvVar(String),
+    ValueTable(String),
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeConflict::Message(id) => write!(f, "message 0x{:X}", id.raw()),
+            MergeConflict::Node(name) => write!(f, "node {}", name),
+            MergeConflict::AttributeDefinition(name) => {
+                write!(f, "attribute definition {}", name)
+            }
+            MergeConflict::EnvVar(name) => write!(f, "environment variable {}", name),
+            MergeConflict::ValueTable(name) => write!(f, "value table {}", name),
+        }
+    }
+}
+
+/// Namespace list written by Vector CANdb++
+const NEW_SYMBOLS: &[&str] = &[
+    "NS_DESC_",
+    "CM_",
+    "BA_DEF_",
+    "BA_",
+    "VAL_",
+    "CAT_DEF_",
+    "CAT_",
+    "FILTER",
+    "BA_DEF_DEF_",
+    "EV_DATA_",
+    "ENVVAR_DATA_",
+    "SGTYPE_",
+    "SGTYPE_VAL_",
+    "BA_DEF_SGTYPE_",
+    "BA_SGTYPE_",
+    "SIG_TYPE_REF_",
+    "VAL_TABLE_",
+    "SIG_GROUP_",
+    "SIG_VALTYPE_",
+    "SIGTYPE_VALTYPE_",
+    "BO_TX_BU_",
+    "BA_DEF_REL_",
+    "BA_REL_",
+    "BA_DEF_DEF_REL_",
+    "BU_SG_REL_",
+    "BU_EV_REL_",
+    "BU_BO_REL_",
+    "SG_MUL_VAL_",
+];
+
+impl Dbc {
+    /// Writes the database back out as DBC text.
+    /// The modelled items are written in canonical order, followed by the statements kept in `raw_unparsed`.
+    pub fn to_dbc_string(&self) -> String {
+        let mut out = String::new();
+        // Writing into a String cannot fail
+        let _ = self.write_dbc(&mut out);
+        out
+    }
+
+    /// Returns a hash of the database content that is stable across runs and Rust versions.
+    /// Message, signal, node and definition order as well as whitespace do not affect it, so
+    /// files differing only in layout hash the same. Computed with 64-bit FNV-1a over the
+    /// DBC text of a canonically sorted copy.
+    pub fn content_hash(&self) -> u64 {
+        let mut canonical = self.clone();
+        canonical.messages.sort_by_key(|msg| msg.message_id.raw());
+        for msg in canonical.messages.iter_mut() {
+            msg.signals.sort_by(|a, b| a.name.cmp(&b.name));
+            msg.transmitters.sort();
+            for sig in msg.signals.iter_mut() {
+                sig.receivers.sort();
+            }
+        }
+        canonical.nodes.sort_by(|a, b| a.name.cmp(&b.name));
+        canonical
+            .attribute_definitions
+            .sort_by_cached_key(|def| format!("{:?}", def));
+        canonical.env_vars.sort_by(|a, b| a.name.cmp(&b.name));
+        canonical
+            .relation_attribute_definitions
+            .sort_by_cached_key(|def| format!("{:?}", def));
+        canonical
+            .relation_attributes
+            .sort_by_cached_key(|attr| format!("{:?}", attr));
+        canonical
+            .signal_groups
+            .sort_by_cached_key(|group| (group.message_id.raw(), group.name.clone()));
+        canonical.value_tables.sort_by(|a, b| a.name.cmp(&b.name));
+        for statement in canonical.raw_unparsed.iter_mut() {
+            *statement = statement.split_whitespace().collect::<Vec<_>>().join(" ");
+        }
+        canonical.raw_unparsed.sort();
+
+        canonical
+            .to_dbc_string()
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    fn write_dbc(&self, out: &mut String) -> fmt::Result {
+        writeln!(out, "VERSION {}", quote(&self.version))?;
+        writeln!(out)?;
+        writeln!(out)?;
+        writeln!(out, "NS_ : ")?;
+        for symbol in NEW_SYMBOLS {
+            writeln!(out, "\t{}", symbol)?;
+        }
+        writeln!(out)?;
+        writeln!(out, "BS_:")?;
+        writeln!(out)?;
+        let nodes: Vec<&str> = self.nodes.iter().map(|node| node.name.as_str()).collect();
+        writeln!(out, "BU_: {}", nodes.join(" "))?;
+        writeln!(out)?;
+        for table in &self.value_tables {
+            write!(out, "VAL_TABLE_ {}", table.name)?;
+            write_value_pairs(out, &table.values)?;
+        }
+        writeln!(out)?;
+
+        for msg in &self.messages {
+            write_message(out, msg)?;
+            writeln!(out)?;
+        }
+
+        for msg in &self.messages {
+            write_transmitters(out, msg)?;
+        }
+        writeln!(out)?;
+
+        for env_var in &self.env_vars {
+            write_env_var(out, env_var)?;
+        }
+        writeln!(out)?;
+        for env_var in &self.env_vars {
+            if let Some(data_size) = env_var.data_size {
+                writeln!(out, "ENVVAR_DATA_ {}: {};", env_var.name, data_size)?;
+            }
+        }
+
+        for node in &self.nodes {
+            if let Some(comment) = &node.comment {
+                writeln!(out, "CM_ BU_ {} {};", node.name, quote(comment))?;
+            }
+        }
+        for msg in &self.messages {
+            write_comments(out, msg)?;
+        }
+
+        for def in &self.attribute_definitions {
+            write_attribute_definition(out, def)?;
+        }
+        for def in &self.relation_attribute_definitions {
+            writeln!(
+                out,
+                "BA_DEF_REL_ {}  {} {};",
+                relation_keyword(def.relation_type),
+                quote(&def.name),
+                value_type_token(&def.value_type)
+            )?;
+        }
+        for def in &self.attribute_definitions {
+            write_attribute_default(out, def)?;
+        }
+        for def in &self.relation_attribute_definitions {
+            if let Some(default) = &def.default {
+                writeln!(
+                    out,
+                    "BA_DEF_DEF_REL_ {} {};",
+                    quote(&def.name),
+                    attribute_value_token(default)
+                )?;
+            }
+        }
+
+        for (name, value) in sorted_attributes(&self.attributes) {
+            writeln!(out, "BA_ {} {};", quote(name), attribute_value_token(value))?;
+        }
+        for node in &self.nodes {
+            for (name, value) in sorted_attributes(&node.attributes) {
+                writeln!(
+                    out,
+                    "BA_ {} BU_ {} {};",
+                    quote(name),
+                    node.name,
+                    attribute_value_token(value)
+                )?;
+            }
+        }
+        for msg in &self.messages {
+            write_attributes(out, msg)?;
+        }
+        for env_var in &self.env_vars {
+            for (name, value) in sorted_attributes(&env_var.attributes) {
+                writeln!(
+                    out,
+                    "BA_ {} EV_ {} {};",
+                    quote(name),
+                    env_var.name,
+                    attribute_value_token(value)
+                )?;
+            }
+        }
+        for attr in &self.relation_attributes {
+            let target = match &attr.target {
+                RelationTarget::Message(id) => format!("BU_BO_REL_ {} {}", attr.node, id.raw()),
+                RelationTarget::Signal(id, signal) => {
+                    format!("BU_SG_REL_ {} SG_ {} {}", attr.node, id.raw(), signal)
+                }
+                RelationTarget::EnvVar(env_var) => format!("BU_EV_REL_ {} {}", attr.node, env_var),
+            };
+            writeln!(
+                out,
+                "BA_REL_ {} {} {};",
+                quote(&attr.name),
+                target,
+                attribute_value_token(&attr.value)
+            )?;
+        }
+
+        for msg in &self.messages {
+            write_value_descriptions(out, msg)?;
+        }
+        writeln!(out)?;
+
+        for group in &self.signal_groups {
+            writeln!(
+                out,
+                "SIG_GROUP_ {} {} {} : {};",
+                group.message_id.raw(),
+                group.name,
+                group.repetitions,
+                group.signals.join(" ")
+            )?;
+        }
+
+        for msg in &self.messages {
+            write_value_types(out, msg)?;
+        }
+        for msg in &self.messages {
+            write_extended_multiplexing(out, msg)?;
+        }
+
+        if !self.raw_unparsed.is_empty() {
+            writeln!(out)?;
+            for statement in &self.raw_unparsed {
+                writeln!(out, "{}", statement)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Message {
+    /// Writes the `BO_` and `SG_` lines of the message together with its
+    /// `BO_TX_BU_`, `CM_`, `BA_`, `VAL_`, `SIG_VALTYPE_` and `SG_MUL_VAL_` entries.
+    /// The `BA_DEF_` and `BA_DEF_DEF_` lines needed to restore the fields derived from
+    /// attribute defaults and enum labels, such as `tx_method`, are written as well.
+    pub fn to_dbc_block(&self) -> String {
+        let mut out = String::new();
+        // Writing into a String cannot fail
+        let _ = self.write_dbc_block(&mut out);
+        out
+    }
+
+    fn write_dbc_block(&self, out: &mut String) -> fmt::Result {
+        write_message(out, self)?;
+        writeln!(out)?;
+        write_transmitters(out, self)?;
+        write_comments(out, self)?;
+        let definitions = block_attribute_definitions(self);
+        for def in &definitions {
+            write_attribute_definition(out, def)?;
+        }
+        for def in &definitions {
+            write_attribute_default(out, def)?;
+        }
+        write_attributes(out, self)?;
+        write_value_descriptions(out, self)?;
+        write_value_types(out, self)?;
+        write_extended_multiplexing(out, self)
+    }
+
+    /// Parses a single message from a DBC text block such as the output of [`Message::to_dbc_block`].
+    /// Enum attributes like `GenSigSendType` are only resolved if the block carries their `BA_DEF_`.
+    pub fn from_dbc_block(text: &str) -> Result<Message, Error> {
+        let mut dbc = Dbc::try_from(text)?;
+        if dbc.messages.len() != 1 {
+            return Err(Error::Invalid(Box::new(dbc), text.to_string()));
+        }
+        Ok(dbc.messages.remove(0))
+    }
+
+    /// Writes a C struct named `<message>_t` holding the raw value of every signal.
+    /// Each field uses the smallest standard integer type containing the signal (`float`/`double`
+    /// for IEEE signals), and its comment carries the position of the least significant bit,
+    /// the size, byte order, scaling and unit.
+    /// The struct is a container for raw values, not an overlay of the payload layout.
+    pub fn to_c_struct(&self) -> String {
+        let mut out = String::new();
+        // Writing into a String cannot fail
+        let _ = self.write_c_struct(&mut out);
+        out
+    }
+
+    fn write_c_struct(&self, out: &mut String) -> fmt::Result {
+        writeln!(
+            out,
+            "/* {} {}, {} bytes */",
+            self.message_name,
+            self.id_hex(),
+            self.message_size
+        )?;
+        writeln!(out, "typedef struct __attribute__((packed)) {{")?;
+        for sig in &self.signals {
+            let lsb = sig.vector_start_bit();
+            write!(
+                out,
+                "    {} {}; /* lsb byte {} bit {}, {} bits {}, factor {} offset {}",
+                c_type(sig),
+                c_identifier(&sig.name),
+                lsb / 8,
+                lsb % 8,
+                sig.signal_size,
+                sig.byte_order,
+                sig.factor,
+                sig.offset
+            )?;
+            if !sig.unit.is_empty() {
+                write!(out, " [{}]", sig.unit.replace("*/", "* /"))?;
+            }
+            writeln!(out, " */")?;
+        }
+        writeln!(out, "}} {}_t;", c_identifier(&self.message_name))
+    }
+}
+
+/// Returns the C type holding the raw value of a signal
+fn c_type(sig: &Signal) -> &'static str {
+    let width = match sig.signal_size {
+        0..=8 => 8,
+        9..=16 => 16,
+        17..=32 => 32,
+        _ => 64,
+    };
+    match (sig.value_type, width) {
+        (ValueType::Float, 32) => "float",
+        (ValueType::Double, 64) => "double",
+        (ValueType::Signed, 8) => "int8_t",
+        (ValueType::Signed, 16) => "int16_t",
+        (ValueType::Signed, 32) => "int32_t",
+        (ValueType::Signed, _) => "int64_t",
+        (_, 8) => "uint8_t",
+        (_, 16) => "uint16_t",
+        (_, 32) => "uint32_t",
+        _ => "uint64_t",
+    }
+}
+
+/// Replaces characters not allowed in a C identifier with `_`
+fn c_identifier(name: &str) -> String {
+    let ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", ident)
+    } else {
+        ident
+    }
+}
+
+fn write_message(out: &mut String, msg: &Message) -> fmt::Result {
+    writeln!(
+        out,
+        "BO_ {} {}: {} {}",
+        msg.message_id.raw(),
+        msg.message_name,
+        msg.message_size,
+        msg.transmitter
+    )?;
+    for sig in &msg.signals {
+        write_signal(out, sig)?;
+    }
+    Ok(())
+}
+
+fn write_transmitters(out: &mut String, msg: &Message) -> fmt::Result {
+    if msg.transmitters.is_empty() {
+        return Ok(());
+    }
+    writeln!(
+        out,
+        "BO_TX_BU_ {} : {};",
+        msg.message_id.raw(),
+        msg.senders().join(",")
+    )
+}
+
+fn write_comments(out: &mut String, msg: &Message) -> fmt::Result {
+    let id = msg.message_id.raw();
+    if let Some(comment) = &msg.comment {
+        writeln!(out, "CM_ BO_ {} {};", id, quote(comment))?;
+    }
+    for sig in &msg.signals {
+        if let Some(comment) = &sig.comment {
+            writeln!(out, "CM_ SG_ {} {} {};", id, sig.name, quote(comment))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_attribute_definition(out: &mut String, def: &AttributeDefinition) -> fmt::Result {
+    let object = match def.object_type {
+        AttributeObjectType::Network => "",
+        AttributeObjectType::Node => "BU_ ",
+        AttributeObjectType::Message => "BO_ ",
+        AttributeObjectType::Signal => "SG_ ",
+        AttributeObjectType::EnvironmentVariable => "EV_ ",
+    };
+    writeln!(
+        out,
+        "BA_DEF_ {} {} {};",
+        object,
+        quote(&def.name),
+        value_type_token(&def.value_type)
+    )
+}
+
+fn write_attribute_default(out: &mut String, def: &AttributeDefinition) -> fmt::Result {
+    match &def.default {
+        Some(default) => writeln!(
+            out,
+            "BA_DEF_DEF_ {} {};",
+            quote(&def.name),
+            attribute_value_token(default)
+        ),
+        None => Ok(()),
+    }
+}
+
+/// Returns the definitions a standalone message block needs to restore the fields derived from
+/// attributes: the default of each field without an explicit `BA_`, and the label of each
+/// explicit enum index that would not resolve to the same label without a definition
+fn block_attribute_definitions(msg: &Message) -> Vec<AttributeDefinition> {
+    let int = |value: Option<u32>| value.map(|value| AttributeValue::Int(value.into()));
+    let label = |value: Option<&str>| value.map(|value| AttributeValue::String(value.to_string()));
+    let flag = |value: Option<bool>| label(value.map(|value| if value { "Yes" } else { "No" }));
+    let frame_format = match (msg.message_id, msg.is_fd) {
+        (MessageID::Standard(_), false) => "StandardCAN",
+        (MessageID::Extended(_), false) => "ExtendedCAN",
+        (MessageID::Standard(_), true) => "StandardCAN_FD",
+        (MessageID::Extended(_), true) => "ExtendedCAN_FD",
+    };
+    let has_frame_format = msg.is_fd || msg.attributes.contains_key("VFrameFormat");
+
+    let message = AttributeObjectType::Message;
+    let mut derived = vec![
+        (
+            message,
+            &msg.attributes,
+            "GenMsgCycleTime",
+            int(msg.cycle_time_opt),
+        ),
+        (
+            message,
+            &msg.attributes,
+            "GenMsgSendType",
+            label(Some(msg.tx_method.as_str()).filter(|method| !method.is_empty())),
+        ),
+        (
+            message,
+            &msg.attributes,
+            "GenMsgStartDelayTime",
+            int(msg.start_delay_time),
+        ),
+        (
+            message,
+            &msg.attributes,
+            "GenMsgNrOfRepetition",
+            int(msg.nr_of_repetitions),
+        ),
+        (
+            message,
+            &msg.attributes,
+            "GenMsgILSupport",
+            flag(msg.il_support),
+        ),
+        (message, &msg.attributes, "NmMessage", flag(msg.nm_message)),
+        (
+            message,
+            &msg.attributes,
+            "DiagRequest",
+            flag(msg.diag_request),
+        ),
+        (
+            message,
+            &msg.attributes,
+            "DiagResponse",
+            flag(msg.diag_response),
+        ),
+        (message, &msg.attributes, "DiagState", flag(msg.diag_state)),
+        (
+            message,
+            &msg.attributes,
+            "VFrameFormat",
+            label(has_frame_format.then_some(frame_format)),
+        ),
+    ];
+    for sig in &msg.signals {
+        let signal = AttributeObjectType::Signal;
+        derived.push((
+            signal,
+            &sig.attributes,
+            "GenSigSendType",
+            label(sig.send_type.as_deref()),
+        ));
+        derived.push((
+            signal,
+            &sig.attributes,
+            "GenSigILSupport",
+            flag(sig.il_support),
+        ));
+    }
+
+    let mut definitions: Vec<AttributeDefinition> = Vec::new();
+    for (object_type, attributes, name, value) in derived {
+        let Some(value) = value else {
+            continue;
+        };
+        // Explicit values are written as BA_ lines, only an enum index needs its label
+        let index = match (attributes.get(name), &value) {
+            (None, _) => None,
+            (Some(AttributeValue::Int(index)), AttributeValue::String(label))
+                if *label != index.to_string() =>
+            {
+                match usize::try_from(*index) {
+                    Ok(index) => Some(index),
+                    Err(_) => continue,
+                }
+            }
+            _ => continue,
+        };
+        let pos = match definitions.iter().position(|def| def.name == name) {
+            Some(pos) => pos,
+            None => {
+                definitions.push(AttributeDefinition {
+                    name: name.to_string(),
+                    object_type,
+                    value_type: AttributeValueType::Int(0, 0),
+                    default: None,
+                });
+                definitions.len() - 1
+            }
+        };
+        let def = &mut definitions[pos];
+        if let AttributeValue::String(label) = &value {
+            if !matches!(def.value_type, AttributeValueType::Enum(_)) {
+                def.value_type = AttributeValueType::Enum(Vec::new());
+            }
+            if let (AttributeValueType::Enum(variants), Some(index)) = (&mut def.value_type, index)
+            {
+                if variants.len() <= index {
+                    variants.resize(index + 1, String::new());
+                }
+                if variants[index].is_empty() {
+                    variants[index] = label.clone();
+                }
+            }
+        }
+        if index.is_none() && def.default.is_none() {
+            def.default = Some(value);
+        }
+    }
+    // Default labels follow the indexed ones so they cannot take an explicit index
+    for def in &mut definitions {
+        if let (AttributeValueType::Enum(variants), Some(AttributeValue::String(label))) =
+            (&mut def.value_type, &def.default)
+            && !variants.contains(label)
+        {
+            variants.push(label.clone());
+        }
+    }
+    definitions
+}
+
+fn write_attributes(out: &mut String, msg: &Message) -> fmt::Result {
+    let id = msg.message_id.raw();
+    for (name, value) in sorted_attributes(&msg.attributes) {
+        writeln!(
+            out,
+            "BA_ {} BO_ {} {};",
+            quote(name),
+            id,
+            attribute_value_token(value)
+        )?;
+    }
+    for sig in &msg.signals {
+        for (name, value) in sorted_attributes(&sig.attributes) {
+            writeln!(
+                out,
+                "BA_ {} SG_ {} {} {};",
+                quote(name),
+                id,
+                sig.name,
+                attribute_value_token(value)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn write_value_descriptions(out: &mut String, msg: &Message) -> fmt::Result {
+    for sig in msg
+        .signals
+        .iter()
+        .filter(|sig| !sig.value_descriptions.is_empty())
+    {
+        write!(out, "VAL_ {} {}", msg.message_id.raw(), sig.name)?;
+        write_value_pairs(out, &sig.value_descriptions)?;
+    }
+    Ok(())
+}
+
+/// Writes value descriptions from the highest value down and terminates the statement
+fn write_value_pairs(out: &mut String, values: &HashMap<i64, String>) -> fmt::Result {
+    let mut values: Vec<_> = values.iter().collect();
+    values.sort_by(|a, b| b.0.cmp(a.0));
+    for (value, description) in values {
+        write!(out, " {} {}", value, quote(description))?;
+    }
+    writeln!(out, " ;")
+}
+
+fn write_value_types(out: &mut String, msg: &Message) -> fmt::Result {
+    for sig in &msg.signals {
+        let valtype = match sig.value_type {
+            ValueType::Float => 1,
+            ValueType::Double => 2,
+            _ => continue,
+        };
+        writeln!(
+            out,
+            "SIG_VALTYPE_ {} {} : {};",
+            msg.message_id.raw(),
+            sig.name,
+            valtype
+        )?;
+    }
+    Ok(())
+}
+
+/// Only signals switched by something other than the message's M signal need `SG_MUL_VAL_`
+fn write_extended_multiplexing(out: &mut String, msg: &Message) -> fmt::Result {
+    let multiplexor = msg
+        .signals
+        .iter()
+        .find(|sig| sig.multiplexer_type == MultiplexIndicator::Multiplexer)
+        .map(|sig| sig.name.as_str());
+    for sig in &msg.signals {
+        if let (Some(value), Some(switch)) = (
+            sig.multiplexer_type.switch_value(),
+            sig.multiplexor_signal.as_deref(),
+        ) && Some(switch) != multiplexor
+        {
+            writeln!(
+                out,
+                "SG_MUL_VAL_ {} {} {} {}-{};",
+                msg.message_id.raw(),
+                sig.name,
+                switch,
+                value,
+                value
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn write_signal(out: &mut String, sig: &Signal) -> fmt::Result {
+    let multiplexer = match sig.multiplexer_type {
+        MultiplexIndicator::Plain => String::new(),
+        MultiplexIndicator::Multiplexer => " M".to_string(),
+        MultiplexIndicator::Multiplexed(value) => format!(" m{}", value),
+        MultiplexIndicator::MultiplexedMultiplexer(value) => format!(" m{}M", value),
+    };
+    let byte_order = match sig.byte_order {
+        ByteOrder::Intel => 1,
+        ByteOrder::Motorola => 0,
+    };
+    let sign = match sig.value_type {
+        ValueType::Unsigned => '+',
+        _ => '-',
+    };
+    let receivers = if sig.receivers.is_empty() {
+        "Vector__XXX".to_string()
+    } else {
+        sig.receivers.join(",")
+    };
+
+    writeln!(
+        out,
+        " SG_ {}{} : {}|{}@{}{} ({},{}) [{}|{}] {} {}",
+        sig.name,
+        multiplexer,
+        sig.start_bit,
+        sig.signal_size,
+        byte_order,
+        sign,
+        sig.factor,
+        sig.offset,
+        sig.min,
+        sig.max,
+        quote(&sig.unit),
+        receivers
+    )
+}
+
+fn write_env_var(out: &mut String, env_var: &EnvVar) -> fmt::Result {
+    // String variables are written the way CANdb++ does, as type 0 with bit 15 of the access code set
+    let (var_type, string_flag) = match env_var.var_type {
+        EnvVarType::Integer => (0, 0),
+        EnvVarType::Float => (1, 0),
+        EnvVarType::String => (0, 0x8000),
+    };
+    let access = match env_var.access_type {
+        AccessType::Unrestricted => 0,
+        AccessType::Read => 1,
+        AccessType::Write => 2,
+        AccessType::ReadWrite => 3,
+    };
+    let access_nodes = if env_var.access_nodes.is_empty() {
+        "Vector__XXX".to_string()
+    } else {
+        env_var.access_nodes.join(",")
+    };
+
+    writeln!(
+        out,
+        "EV_ {}: {} [{}|{}] {} {} {} DUMMY_NODE_VECTOR{:X}  {};",
+        env_var.name,
+        var_type,
+        env_var.min,
+        env_var.max,
+        quote(&env_var.unit),
+        env_var.initial_value,
+        env_var.ev_id,
+        access | string_flag,
+        access_nodes
+    )
+}
+
+/// Quotes a DBC string, escaping backslashes and quotes
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn attribute_value_token(value: &AttributeValue) -> String {
+    match value {
+        AttributeValue::String(text) => quote(text),
+        value => value.to_string(),
+    }
+}
+
+fn value_type_token(value_type: &AttributeValueType) -> String {
+    match value_type {
+        AttributeValueType::Int(min, max) => format!("INT {} {}", min, max),
+        AttributeValueType::Hex(min, max) => format!("HEX {} {}", min, max),
+        AttributeValueType::Float(min, max) => format!("FLOAT {} {}", min, max),
+        AttributeValueType::String => "STRING ".to_string(),
+        AttributeValueType::Enum(variants) => {
+            let variants: Vec<String> = variants.iter().map(|v| quote(v)).collect();
+            format!("ENUM  {}", variants.join(","))
+        }
+    }
+}
+
+fn relation_keyword(relation_type: RelationType) -> &'static str {
+    match relation_type {
+        RelationType::NodeMessage => "BU_BO_REL_",
+        RelationType::NodeSignal => "BU_SG_REL_",
+        RelationType::NodeEnvVar => "BU_EV_REL_",
+    }
+}
+
+/// Attribute maps are unordered; sort by name so output is deterministic
+fn sorted_attributes(
+    attributes: &HashMap<String, AttributeValue>,
+) -> Vec<(&String, &AttributeValue)> {
+    let mut sorted: Vec<_> = attributes.iter().collect();
+    sorted.sort_by_key(|(name, _)| name.as_str());
+    sorted
+}
+
+impl TryFrom<&str> for Dbc {
+    type Error = Error;
+
+    fn try_from(dbc_input: &str) -> Result<Self, Self::Error> {
+        Dbc::parse_with_warnings(dbc_input).map(|(dbc, _)| dbc)
+    }
+}
+
+impl Dbc {
+    /// Parses a DBC like `Dbc::try_from`, additionally returning the constructs
+    /// that were accepted but looked malformed
+    pub fn parse_with_warnings(dbc_input: &str) -> Result<(Dbc, Vec<ParseWarning>), Error> {
+        Self::parse_passes(dbc_input, &ParseOptions::default())
+    }
+
+    /// Parses a DBC like `Dbc::try_from` with the given options
+    pub fn parse_with_options(dbc_input: &str, options: &ParseOptions) -> Result<Dbc, Error> {
+        Self::parse_passes(dbc_input, options).map(|(dbc, _)| dbc)
+    }
+
+    /// Parses only the message and signal layout, which is considerably faster on large files.
+    ///
+    /// The comment, attribute, initial value and value description passes are skipped. Comments,
+    /// attributes, value descriptions and the fields derived from attributes, such as cycle times
+    /// and send types, are left empty, and `VFrameFormat` is not applied. Environment variables,
+    /// signal groups, value tables and unmodelled statements are not collected either.
+    /// `SIG_VALTYPE_` and `SG_MUL_VAL_` are still parsed.
+    pub fn parse_layout_only(dbc_input: &str) -> Result<Dbc, Error> {
+        let options = ParseOptions {
+            layout_only: true,
+            ..ParseOptions::default()
+        };
+        Self::parse_with_options(dbc_input, &options)
+    }
+
+    fn parse_passes(
+        dbc_input: &str,
+        options: &ParseOptions,
+    ) -> Result<(Dbc, Vec<ParseWarning>), Error> {
+        let name = &options.name()?;
+        let layout_only = options.layout_only;
+        if options.strict
+            && let Some((line, text)) = find_malformed_signal(dbc_input, name)?
+        {
+            return Err(Error::MalformedSignal(line, text));
+        }
+        let mut warnings = Vec::new();
+        let (attribute_definitions, undefined_defaults) = if layout_only {
+            (Vec::new(), HashMap::new())
+        } else {
+            parse_attribute_definitions(dbc_input)
+        };
+        // A default without a definition, like a lone `BA_DEF_DEF_ "GenMsgCycleTime" 100;`,
+        // still applies to the fields read from it, but is not kept as a definition
+        let mut field_definitions = attribute_definitions.clone();
+        field_definitions.extend(
+            undefined_defaults
+                .into_iter()
+                .map(|(name, default)| implied_definition(name, default)),
+        );
+        let mut message_ids = HashMap::new();
+        let mut dbc = Dbc {
+            version: parse_version(dbc_input),
+            messages: parse_message(
+                dbc_input,
+                &field_definitions,
+                layout_only,
+                name,
+                &mut warnings,
+                &mut message_ids,
+            )?,
+            nodes: parse_nodes(dbc_input, layout_only),
+            attribute_definitions,
+            env_vars: Vec::new(),
+            relation_attribute_definitions: Vec::new(),
+            relation_attributes: Vec::new(),
+            signal_groups: Vec::new(),
+            value_tables: Vec::new(),
+            attributes: HashMap::new(),
+            raw_unparsed: Vec::new(),
+        };
+        if !layout_only {
+            dbc.env_vars = parse_env_vars(dbc_input);
+            dbc.relation_attribute_definitions = parse_relation_attribute_definitions(dbc_input);
+            dbc.relation_attributes = parse_relation_attributes(dbc_input, name, &message_ids)?;
+            dbc.signal_groups = parse_signal_groups(dbc_input, name, &message_ids)?;
+            dbc.value_tables = parse_value_tables(dbc_input, name)?;
+            dbc.attributes = parse_network_attributes(dbc_input);
+            dbc.raw_unparsed = parse_unparsed_statements(dbc_input, &dbc.attribute_definitions);
+        }
+
+        if dbc.messages.is_empty() {
+            return Err(Error::Invalid(Box::new(dbc), dbc_input.to_string()));
+        }
+        Ok((dbc, warnings))
+    }
+}
+
+/// Assembles a [`Dbc`] programmatically
+#[derive(Clone, Debug, Default)]
+pub struct DbcBuilder {
+    version: String,
+    nodes: Vec<Node>,
+    messages: Vec<Message>,
+}
+
+impl DbcBuilder {
+    pub fn new() -> DbcBuilder {
+        DbcBuilder::default()
+    }
+
+    pub fn version(mut self, version: &str) -> DbcBuilder {
+        self.version = version.to_string();
+        self
+    }
+
+    /// Adds a `BU_` node without comment or attributes
+    pub fn node(mut self, name: &str) -> DbcBuilder {
+        self.nodes.push(Node {
+            name: name.to_string(),
+            comment: None,
+            attributes: HashMap::new(),
+        });
+        self
+    }
+
+    pub fn message(mut self, message: Message) -> DbcBuilder {
+        self.messages.push(message);
+        self
+    }
+
+    pub fn build(self) -> Dbc {
+        Dbc {
+            version: self.version,
+            messages: self.messages,
+            nodes: self.nodes,
+            attribute_definitions: Vec::new(),
+            env_vars: Vec::new(),
+            relation_attribute_definitions: Vec::new(),
+            relation_attributes: Vec::new(),
+            signal_groups: Vec::new(),
+            value_tables: Vec::new(),
+            attributes: HashMap::new(),
+            raw_unparsed: Vec::new(),
+        }
+    }
+
+    /// Builds the database and runs [`Dbc::validate`] on it. Fails with the findings of
+    /// [`Severity::Error`]; findings of [`Severity::Warning`] are allowed.
+    pub fn build_validated(self) -> Result<Dbc, Vec<ValidationWarning>> {
+        let dbc = self.build();
+        let errors: Vec<ValidationWarning> = dbc
+            .validate()
+            .into_iter()
+            .filter(|warning| warning.severity() == Severity::Error)
+            .collect();
+        if errors.is_empty() {
+            Ok(dbc)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Options for [`Dbc::parse_with_options`]. The default parses like `Dbc::try_from`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Regex matching one message or signal name, e.g. `[\w.-]+` for exports using `-` or `.`.
+    /// Defaults to `\w+`. It must not contain capture groups. A permissive pattern can swallow
+    /// neighbouring tokens (`[^:]+` would take the `m0` of a multiplexed signal into its name),
+    /// so only add the characters the file actually uses.
+    pub name_pattern: Option<String>,
+    /// Parses only the message and signal layout, see [`Dbc::parse_layout_only`]
+    pub layout_only: bool,
+    /// Fails with [`Error::MalformedSignal`] on the first `SG_` line that is not a well-formed
+    /// signal instead of skipping it
+    pub strict: bool,
+}
+
+impl ParseOptions {
+    /// Returns the validated name pattern as a non-capturing group
+    fn name(&self) -> Result<String, Error> {
+        let Some(pattern) = self.name_pattern.as_deref() else {
+            return Ok(NAME.to_string());
+        };
+        // Validated as embedded, a `#` comment under `(?x)` would swallow the closing `)`
+        let name = format!("(?:{})", pattern);
+        match Regex::new(&name) {
+            Ok(re) if re.captures_len() == 1 => Ok(name),
+            _ => Err(Error::NamePattern(pattern.to_string())),
+        }
+    }
+}
+
+/// A decoded frame as produced by [`Decoder::decode_frame`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedFrame {
+    pub message_name: String,
+    pub signals: HashMap<String, f64>,
+}
+
+/// Decodes a stream of frames against one database.
+/// The message ID index is built once in [`Decoder::new`] and reused for every frame.
+#[derive(Clone, Debug)]
+pub struct Decoder<'a> {
+    dbc: &'a Dbc,
+    index: HashMap<u32, usize>,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(dbc: &'a Dbc) -> Decoder<'a> {
+        let index = dbc
+            .messages
+            .iter()
+            .enumerate()
+            .map(|(i, msg)| (msg.message_id.raw(), i))
+            .collect();
+        Decoder { dbc, index }
+    }
+
+    /// Decodes a frame whose ID is given in the format returned by [`MessageID::raw`].
+    /// Returns `None` if the database has no message with that ID.
+    pub fn decode_frame(&self, id: u32, data: &[u8]) -> Option<DecodedFrame> {
+        let msg = &self.dbc.messages[*self.index.get(&id)?];
+        Some(DecodedFrame {
+            message_name: msg.message_name.clone(),
+            signals: msg.decode(data),
+        })
+    }
+}
+
+/// With `layout_only` the comment, attribute and value description passes are skipped.
+/// `message_ids` receives the ID of each message keyed by the ID written in its `BO_` line.
+fn parse_message(
+    dbc_input: &str,
+    attribute_definitions: &[AttributeDefinition],
+    layout_only: bool,
+    name: &str,
+    warnings: &mut Vec<ParseWarning>,
+    message_ids: &mut HashMap<u32, MessageID>,
+) -> Result<Vec<Message>, Error> {
+    let message_names = parse_message_name(dbc_input, name)?;
+    let message_size = parse_message_size(dbc_input, name)?;
+    let message_transmitters = parse_message_transmitters(dbc_input, name)?;
+    let additional_transmitters = parse_additional_transmitters(dbc_input, &message_transmitters);
+    let mut comments = HashMap::new();
+    let mut attributes = HashMap::new();
+    let mut default_cycles = None;
+    let mut explicit_cycles = HashMap::new();
+    let mut default_tx_method = String::new();
+    let mut explicit_tx_methods = HashMap::new();
+    let mut default_frame_format = None;
+    let mut value_descriptions = HashMap::new();
+    if !layout_only {
+        comments = parse_message_comments(dbc_input);
+        attributes = parse_message_attributes(dbc_input);
+        default_cycles = match attribute_default(attribute_definitions, "GenMsgCycleTime") {
+            Some(AttributeValue::Int(value)) => u32::try_from(*value).ok(),
+            _ => None,
+        };
+        explicit_cycles = parse_explicit_cycle_time(dbc_input);
+        (default_tx_method, explicit_tx_methods) = parse_tx_methods(dbc_input);
+        default_frame_format = attribute_default(attribute_definitions, "VFrameFormat");
+        value_descriptions = parse_value_descriptions(dbc_input, name)?;
+    }
+    let signals = parse_signals(
+        dbc_input,
+        &value_descriptions,
+        attribute_definitions,
+        layout_only,
+        name,
+        warnings,
+    )?;
+
+    let mut message = Vec::new();
+
+    for (id, message_name) in message_names {
+        let cycle_time_opt = explicit_cycles.get(&id).copied().or(default_cycles);
+        let message_size = message_size.get(&id).copied().unwrap_or(0);
+        let mut message_signals = signals.get(&id).cloned().unwrap_or_else(Vec::new);
+        let transmitter = message_transmitters
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| "Vector__XXX".to_string());
+        let transmitters = additional_transmitters
+            .get(&id)
+            .cloned()
+            .unwrap_or_default();
+        let tx_method = explicit_tx_methods
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| default_tx_method.clone());
+
+        // Multiplexed signals without an SG_MUL_VAL_ entry are switched by the message's M signal
+        let multiplexor = message_signals
+            .iter()
+            .find(|sig| sig.multiplexer_type == MultiplexIndicator::Multiplexer)
+            .map(|sig| sig.name.clone());
+        for sig in message_signals.iter_mut() {
+            if sig.is_multiplexed() && sig.multiplexor_signal.is_none() {
+                sig.multiplexor_signal = multiplexor.clone();
+            }
+        }
+
+        let attributes = attributes.remove(&id).unwrap_or_default();
+        let frame_format = attributes
+            .get("VFrameFormat")
+            .or(default_frame_format)
+            .and_then(|value| resolve_enum_label(attribute_definitions, "VFrameFormat", value));
+        let (message_id, is_fd) = match frame_format {
+            Some(label) => apply_frame_format(id, &label),
+            None => (MessageID::from_dbc(id), false),
+        };
+        message_ids.insert(id, message_id);
+        // FD exports may write the length code instead of the byte count, which is unambiguous
+        // for the codes that are no valid FD length themselves
+        let (message_size, dlc) = match message_size {
+            9..=11 | 13..=15 if is_fd => (
+                FD_PAYLOAD_LENGTHS[message_size as usize],
+                message_size as u8,
+            ),
+            _ => (message_size, dlc_for_length(message_size)),
+        };
+        let attribute_u32 = |name: &str| match attributes
+            .get(name)
+            .or_else(|| attribute_default(attribute_definitions, name))
+        {
+            Some(AttributeValue::Int(value)) => u32::try_from(*value).ok(),
+            _ => None,
+        };
+        let start_delay_time = attribute_u32("GenMsgStartDelayTime");
+        let nr_of_repetitions = attribute_u32("GenMsgNrOfRepetition");
+        let flag = |name: &str| attribute_flag(attribute_definitions, &attributes, name);
+        let il_support = flag("GenMsgILSupport");
+        let nm_message = flag("NmMessage");
+        let diag_request = flag("DiagRequest");
+        let diag_response = flag("DiagResponse");
+        let diag_state = flag("DiagState");
+
+        message.push(Message {
+            message_name,
+            message_id,
+            message_size,
+            dlc,
+            is_fd,
+            cycle_time: cycle_time_opt.unwrap_or(0),
+            cycle_time_opt,
+            start_delay_time,
+            nr_of_repetitions,
+            il_support,
+            nm_message,
+            diag_request,
+            diag_response,
+            diag_state,
+            transmitter,
+            transmitters,
+            tx_method,
+            signals: message_signals,
+            attributes,
+            comment: comments.remove(&id),
+        });
+    }
+
+    Ok(message)
+}
+
+fn parse_nodes(dbc_input: &str, layout_only: bool) -> Vec<Node> {
+    let re_nodes = Regex::new(r#"(?m)^\s*BU_\s*:([^\n]*)"#).unwrap();
+    let mut comments = HashMap::new();
+    let mut attributes = HashMap::new();
+    if !layout_only {
+        comments = parse_node_comments(dbc_input);
+        attributes = parse_node_attributes(dbc_input);
+    }
+
+    re_nodes
+        .captures(dbc_input)
+        .map(|cap| {
+            cap[1]
+                .split_whitespace()
+                .map(|name| Node {
+                    name: name.to_string(),
+                    comment: comments.remove(name),
+                    attributes: attributes.remove(name).unwrap_or_default(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Pattern for a quoted comment body, which may span several lines and contain `\"` escapes.
+/// The `CM_` patterns using it are deliberately not anchored to the line start, so indented
+/// comments and comments following other statements on the same line are found as well.
+const COMMENT_TEXT: &str = r#""((?s:[^"\\]|\\.)*)"\s*;"#;
+
+/// Resolves the `\"` and `\\` escapes of a quoted DBC string
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.push(chars.next().unwrap_or('\\')),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+fn parse_node_comments(dbc_input: &str) -> HashMap<String, String> {
+    let re_comment = Regex::new(&format!(r#"CM_\s+BU_\s+(\w+)\s+{}"#, COMMENT_TEXT)).unwrap();
+    let mut map = HashMap::new();
+
+    for cap in re_comment.captures_iter(dbc_input) {
+        map.insert(cap[1].to_string(), unescape(&cap[2]));
+    }
+    map
+}
+
+fn parse_message_comments(dbc_input: &str) -> HashMap<u32, String> {
+    let re_comment = Regex::new(&format!(r#"CM_\s+BO_\s+(\d+)\s+{}"#, COMMENT_TEXT)).unwrap();
+    let mut map = HashMap::new();
+
+    for cap in re_comment.captures_iter(dbc_input) {
+        if let Ok(id) = cap[1].parse::<u32>() {
+            map.insert(id, unescape(&cap[2]));
+        }
+    }
+    map
+}
+
+fn parse_signal_comments(
+    dbc_input: &str,
+    name: &str,
+) -> Result<HashMap<(u32, String), String>, Error> {
+    let re_comment = name_regex(
+        &format!(r#"CM_\s+SG_\s+(\d+)\s+({})\s+{}"#, name, COMMENT_TEXT),
+        name,
+    )?;
+    let mut map = HashMap::new();
+
+    for cap in re_comment.captures_iter(dbc_input) {
+        if let Ok(id) = cap[1].parse::<u32>() {
+            map.insert((id, cap[2].to_string()), unescape(&cap[3]));
+        }
+    }
+    Ok(map)
+}
+
+/// `DUMMY_NODE_VECTORn` tokens are placeholders, not node names
+fn is_dummy_node(name: &str) -> bool {
+    name.starts_with("DUMMY_NODE_VECTOR")
+}
+
+fn parse_env_vars(dbc_input: &str) -> Vec<EnvVar> {
+    let re_env = Regex::new(r#"EV_\s+(\w+)\s*:\s*(\d+)\s*\[([^|]+)\|([^\]]+)\]\s*"([^"]*)"\s+(\S+)\s+(\d+)\s+DUMMY_NODE_VECTOR([0-9A-Fa-f]+)\s*([^;]*);"#).unwrap();
+    let re_data = Regex::new(r#"ENVVAR_DATA_\s+(\w+)\s*:\s*(\d+)\s*;"#).unwrap();
+    let data_sizes: HashMap<String, u32> = re_data
+        .captures_iter(dbc_input)
+        .filter_map(|cap| Some((cap[1].to_string(), cap[2].parse::<u32>().ok()?)))
+        .collect();
+    let mut attributes = parse_env_var_attributes(dbc_input);
+    let mut env_vars = Vec::new();
+
+    for cap in re_env.captures_iter(dbc_input) {
+        if let (Ok(min), Ok(max), Ok(initial_value), Ok(ev_id), Ok(access)) = (
+            parse_number(&cap[3]),
+            parse_number(&cap[4]),
+            parse_number(&cap[6]),
+            cap[7].parse::<u64>(),
+            u32::from_str_radix(&cap[8], 16),
+        ) {
+            // Bit 15 of the access code marks string variables in older files
+            let var_type = if access & 0x8000 != 0 {
+                EnvVarType::String
+            } else {
+                match &cap[2] {
+                    "1" => EnvVarType::Float,
+                    "2" => EnvVarType::String,
+                    _ => EnvVarType::Integer,
+                }
+            };
+            let access_type = match access & 0x3 {
+                1 => AccessType::Read,
+                2 => AccessType::Write,
+                3 => AccessType::ReadWrite,
+                _ => AccessType::Unrestricted,
+            };
+            let access_nodes = cap[9]
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty() && s != "Vector__XXX" && !is_dummy_node(s))
+                .collect();
+
+            env_vars.push(EnvVar {
+                name: cap[1].to_string(),
+                var_type,
+                min,
+                max,
+                unit: cap[5].to_string(),
+                initial_value,
+                ev_id,
+                access_type,
+                access_nodes,
+                data_size: data_sizes.get(&cap[1]).copied(),
+                attributes: attributes.remove(&cap[1]).unwrap_or_default(),
+            });
+        }
+    }
+    env_vars
+}
+
+/// Pattern for a `BO_` message ID, which hand-edited files sometimes write in hex
+const MESSAGE_ID: &str = r"(0[xX][0-9A-Fa-f]+|\d+)";
+
+/// Pattern of a message or signal name unless `ParseOptions::name_pattern` overrides it
+const NAME: &str = r"\w+";
+
+/// Per-signal items keyed by message ID and signal name
+type SignalMap<T> = HashMap<(u32, String), T>;
+
+/// Parses a decimal or `0x`-prefixed message ID
+fn parse_message_id(token: &str) -> Result<u32, std::num::ParseIntError> {
+    match token
+        .strip_prefix("0x")
+        .or_else(|| token.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => token.parse::<u32>(),
+    }
+}
+
+/// Parses a numeric field such as a signal factor or bound. Integers (`1`), decimals (`1.0`)
+/// and exponent forms (`1E2`) all yield the same `f64`. Surrounding whitespace is ignored,
+/// MSVC's `1.#INF` spelling is accepted, and bounds written with too few digits to stay
+/// within `f64` (e.g. `1.79769313486232E+308`) are clamped to `f64::MAX`.
+fn parse_number(token: &str) -> Result<f64, std::num::ParseFloatError> {
+    let token = token.trim();
+    let (negative, magnitude) = match token.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, token.strip_prefix('+').unwrap_or(token)),
+    };
+    let value = if magnitude.eq_ignore_ascii_case("1.#INF") {
+        f64::INFINITY
+    } else {
+        let value = magnitude.parse::<f64>()?;
+        if value.is_infinite() && magnitude.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+            f64::MAX
+        } else {
+            value
+        }
+    };
+    Ok(if negative { -value } else { value })
+}
+
+/// Returns the message names in file order. A repeated ID keeps its first position and last name.
+fn parse_message_name(dbc_input: &str, name: &str) -> Result<Vec<(u32, String)>, Error> {
+    let re_name = name_regex(&format!(r#"BO_\s+{}\s+({})\s*:"#, MESSAGE_ID, name), name)?;
+    let mut names: Vec<(u32, String)> = Vec::new();
+    let mut positions: HashMap<u32, usize> = HashMap::new();
+
+    for cap in re_name.captures_iter(dbc_input) {
+        if let (Ok(id), Ok(name)) = (parse_message_id(&cap[1]), cap[2].parse::<String>()) {
+            match positions.get(&id) {
+                Some(&pos) => names[pos].1 = name,
+                None => {
+                    positions.insert(id, names.len());
+                    names.push((id, name));
+                }
+            }
+        }
+    }
+    Ok(names)
+}
+
+fn parse_message_size(dbc_input: &str, name: &str) -> Result<HashMap<u32, u64>, Error> {
+    let re_size = name_regex(
+        &format!(r#"BO_\s+{}\s+{}\s*:\s+(\d+)"#, MESSAGE_ID, name),
+        name,
+    )?;
+    let mut map = HashMap::new();
+
+    for cap in re_size.captures_iter(dbc_input) {
+        if let (Ok(id), Ok(size)) = (parse_message_id(&cap[1]), cap[2].parse::<u64>()) {
+            map.insert(id, size);
+        }
+    }
+    Ok(map)
+}
+
+fn parse_message_transmitters(dbc_input: &str, name: &str) -> Result<HashMap<u32, String>, Error> {
+    // The transmitter must be on the BO_ line itself, so it may be omitted
+    let re_transmitter = name_regex(
+        &format!(r#"BO_\s+{}\s+{}\s*:\s+\d+[ \t]+(\w+)"#, MESSAGE_ID, name),
+        name,
+    )?;
+    let mut map = HashMap::new();
+
+    for cap in re_transmitter.captures_iter(dbc_input) {
+        if let Ok(id) = parse_message_id(&cap[1]) {
+            // A DUMMY_NODE_VECTOR placeholder means no transmitter, like Vector__XXX
+            let transmitter = if is_dummy_node(&cap[2]) {
+                "Vector__XXX".to_string()
+            } else {
+                cap[2].to_string()
+            };
+            map.insert(id, transmitter);
+        }
+    }
+    Ok(map)
+}
+
+/// Returns the `BO_TX_BU_` nodes of each message, without the `BO_` transmitter, placeholders
+/// and repetitions
+fn parse_additional_transmitters(
+    dbc_input: &str,
+    message_transmitters: &HashMap<u32, String>,
+) -> HashMap<u32, Vec<String>> {
+    let re_tx_bu = Regex::new(r#"BO_TX_BU_\s+(\d+)\s*:\s*([^;]*);"#).unwrap();
+    let mut map = HashMap::new();
+
+    for cap in re_tx_bu.captures_iter(dbc_input) {
+        if let Ok(id) = cap[1].parse::<u32>() {
+            let mut seen: HashSet<&str> = HashSet::from(["Vector__XXX"]);
+            if let Some(transmitter) = message_transmitters.get(&id) {
+                seen.insert(transmitter);
+            }
+            let mut transmitters: Vec<String> = Vec::new();
+            for node in cap[2].split(',').map(str::trim) {
+                if !node.is_empty() && !is_dummy_node(node) && seen.insert(node) {
+                    transmitters.push(node.to_string());
+                }
+            }
+            map.insert(id, transmitters);
+        }
+    }
+    map
+}
+
+/// Classifies a `BO_` ID by its `VFrameFormat` label (`StandardCAN`, `ExtendedCAN`,
+/// `StandardCAN_FD`, `ExtendedCAN_FD`, `J1939PG`), falling back to bit 31 for unknown labels.
+/// A standard label on an ID wider than 11 bits is ignored.
+fn apply_frame_format(id: u32, label: &str) -> (MessageID, bool) {
+    let is_fd = label.ends_with("_FD");
+    let raw = id & 0x1FFF_FFFF;
+    let message_id = match label.trim_end_matches("_FD") {
+        "ExtendedCAN" | "J1939PG" => MessageID::Extended(raw),
+        "StandardCAN" if raw < 0x800 => MessageID::Standard(raw as u16),
+        _ => MessageID::from_dbc(id),
+    };
+    (message_id, is_fd)
+}
+
+fn parse_explicit_cycle_time(dbc_input: &str) -> HashMap<u32, u32> {
+    let re_explicit = Regex::new(r#"BA_\s+"GenMsgCycleTime"\s+BO_\s+(\d+)\s+(\d+)\s*;"#).unwrap();
+    let mut map = HashMap::new();
+
+    for cap in re_explicit.captures_iter(dbc_input) {
+        if let (Ok(id), Ok(cycle)) = (cap[1].parse::<u32>(), cap[2].parse::<u32>()) {
+            map.insert(id, cycle);
+        }
+    }
+    map
+}
+
+fn parse_signals(
+    dbc_input: &str,
+    value_descriptions: &HashMap<(u32, String), HashMap<i64, String>>,
+    attribute_definitions: &[AttributeDefinition],
+    layout_only: bool,
+    name: &str,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<HashMap<u32, Vec<Signal>>, Error> {
+    let re_signal = signal_regex(name)?;
+    let sig_valtypes = parse_sig_valtypes(dbc_input, name)?;
+    let multiplexor_signals = parse_multiplexor_signals(dbc_input, name)?;
+    let mut initial_values = HashMap::new();
+    let mut signal_attributes = HashMap::new();
+    let mut default_send_type = None;
+    let mut comments = HashMap::new();
+    if !layout_only {
+        initial_values = parse_initial_values(dbc_input, name)?;
+        signal_attributes = parse_signal_attributes(dbc_input, name)?;
+        default_send_type = attribute_default(attribute_definitions, "GenSigSendType");
+        comments = parse_signal_comments(dbc_input, name)?;
+    }
+    let mut signals_map: HashMap<u32, Vec<Signal>> = HashMap::new();
+    let re_message = name_regex(&format!(r#"BO_\s+{}\s+{}\s*:"#, MESSAGE_ID, name), name)?;
+
+    // A BO_ block ends at the first line starting another statement, a malformed SG_ line
+    // or unknown text does not end it. Signals found outside a block are attached to the
+    // next message, as seen in reordered merges, or to the last one if no message follows.
+    let mut signal_lines = Vec::new();
+    let mut orphans: Vec<regex::Captures> = Vec::new();
+    let mut current_message_id = None;
+    let mut last_message_id = None;
+    for line in dbc_input.lines() {
+        if let Some(msg_cap) = re_message.captures(line)
+            && let Ok(id) = parse_message_id(&msg_cap[1])
+        {
+            current_message_id = Some(id);
+            last_message_id = Some(id);
+            signals_map.entry(id).or_default();
+            for cap in orphans.drain(..) {
+                warnings.push(ParseWarning::ReorderedSignal {
+                    message_id: id,
+                    signal: cap[1].to_string(),
+                });
+                signal_lines.push((id, cap));
+            }
+        } else if let Some(cap) = re_signal.captures(line) {
+            match current_message_id {
+                Some(id) => signal_lines.push((id, cap)),
+                None => orphans.push(cap),
+            }
+        } else if !matches!(
+            classify_line(line),
+            LineKind::Blank | LineKind::Signal | LineKind::Unknown
+        ) {
+            current_message_id = None;
+        }
+    }
+    // Without any message the file is rejected anyway
+    if let Some(id) = last_message_id {
+        for cap in orphans {
+            warnings.push(ParseWarning::ReorderedSignal {
+                message_id: id,
+                signal: cap[1].to_string(),
+            });
+            signal_lines.push((id, cap));
+        }
+    }
+
+    for (current_message_id, cap) in signal_lines {
+        if let (Ok(start_bit), Ok(signal_size), Ok(factor), Ok(offset), Ok(min), Ok(max)) = (
+            cap[3].parse::<u64>(),
+            cap[4].parse::<u64>(),
+            parse_number(&cap[7]),
+            parse_number(&cap[8]),
+            parse_number(&cap[9]),
+            parse_number(&cap[10]),
+        ) {
+            let signal_name = cap[1].to_string();
+            let byte_order = if &cap[5] == "1" {
+                ByteOrder::Intel
+            } else {
+                ByteOrder::Motorola
+            };
             let mut value_type = if &cap[6] == "+" {
                 ValueType::Unsigned
             } else {
@@ -467,20 +4051,35 @@ fn parse_signals(
                 MultiplexIndicator::Plain
             } else if multiplexer_info == "M" {
                 MultiplexIndicator::Multiplexer
-            } else if multiplexer_info.starts_with("m") {
-                MultiplexIndicator::Multiplexed
+            } else if let Some(Ok(switch_value)) = multiplexer_info
+                .strip_prefix('m')
+                .and_then(|token| token.strip_suffix('M'))
+                .map(str::parse::<u64>)
+            {
+                MultiplexIndicator::MultiplexedMultiplexer(switch_value)
+            } else if let Some(Ok(switch_value)) =
+                multiplexer_info.strip_prefix('m').map(str::parse::<u64>)
+            {
+                MultiplexIndicator::Multiplexed(switch_value)
             } else {
+                warnings.push(ParseWarning::UnknownMultiplexIndicator {
+                    message_id: current_message_id,
+                    signal: signal_name.clone(),
+                    token: multiplexer_info,
+                });
                 MultiplexIndicator::Plain
             };
 
-            // Parse receivers from the end of the line
+            // Parse receivers from the end of the line, separated by commas or spaces. Empty entries,
+            // as left by a trailing comma in `ECU1,ECU2,`, are dropped
             let receivers_str = cap.get(12).map_or("", |m| m.as_str()).trim();
             let receivers: Vec<String> = if receivers_str.is_empty() {
                 Vec::new()
             } else {
                 receivers_str
-                    .split(',')
-                    .map(|s| s.trim().to_string())
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|s| !s.is_empty() && *s != "Vector__XXX" && !is_dummy_node(s))
+                    .map(|s| s.to_string())
                     .collect()
             };
 
@@ -494,6 +4093,24 @@ fn parse_signals(
                 .copied()
                 .unwrap_or(0.0);
 
+            let multiplexor_signal = multiplexor_signals
+                .get(&(current_message_id, signal_name.clone()))
+                .cloned();
+
+            let attributes = signal_attributes
+                .remove(&(current_message_id, signal_name.clone()))
+                .unwrap_or_default();
+
+            let comment = comments.remove(&(current_message_id, signal_name.clone()));
+
+            let send_type = attributes
+                .get("GenSigSendType")
+                .or(default_send_type)
+                .and_then(|value| {
+                    resolve_enum_label(attribute_definitions, "GenSigSendType", value)
+                });
+            let il_support = attribute_flag(attribute_definitions, &attributes, "GenSigILSupport");
+
             let signal = Signal {
                 name: signal_name,
                 start_bit,
@@ -508,7 +4125,12 @@ fn parse_signals(
                 receivers,
                 value_descriptions: signal_value_descriptions,
                 multiplexer_type,
+                multiplexor_signal,
                 initial_value,
+                send_type,
+                il_support,
+                attributes,
+                comment,
             };
 
             if let Some(signals) = signals_map.get_mut(&current_message_id) {
@@ -517,64 +4139,725 @@ fn parse_signals(
         }
     }
 
-    signals_map
+    Ok(signals_map)
+}
+
+/// Compiles a statement regex embedding the name pattern, which only a user-supplied
+/// `ParseOptions::name_pattern` can make invalid
+fn name_regex(regex: &str, name: &str) -> Result<Regex, Error> {
+    Regex::new(regex).map_err(|_| Error::NamePattern(name.to_string()))
+}
+
+/// Matches an `SG_` line, capturing the name, multiplex indicator, start bit, size, byte order,
+/// sign, factor, offset, min, max, unit and receivers. The parentheses around factor and offset
+/// may be missing, as in the legacy form `0|8@1+ 1,0 [0|0]`.
+fn signal_regex(name: &str) -> Result<Regex, Error> {
+    name_regex(
+        &format!(
+            r#"SG_\s+({})\s*(\w*)\s*:\s*(\d+)\s*\|\s*(\d+)\s*@\s*([01])\s*([+-])\s*\(?([^,()]+),([^)\[]+?)\)?\s*\[([^|]+)\|([^\]]+)\]\s*"([^"]*)"\s*(.*)"#,
+            name
+        ),
+        name,
+    )
+}
+
+/// Returns the 1-based number and text of the first line starting with `SG_` that is not a
+/// well-formed signal, as rejected by [`ParseOptions::strict`]
+fn find_malformed_signal(dbc_input: &str, name: &str) -> Result<Option<(usize, String)>, Error> {
+    let re_signal = signal_regex(name)?;
+    Ok(dbc_input.lines().enumerate().find_map(|(idx, line)| {
+        let trimmed = line.trim();
+        let is_signal = trimmed
+            .strip_prefix("SG_")
+            .is_some_and(|rest| rest.starts_with(char::is_whitespace));
+        let well_formed = re_signal.captures(trimmed).is_some_and(|cap| {
+            cap[3].parse::<u64>().is_ok()
+                && cap[4].parse::<u64>().is_ok()
+                && (7..=10).all(|i| parse_number(&cap[i]).is_ok())
+        });
+        (is_signal && !well_formed).then(|| (idx + 1, trimmed.to_string()))
+    }))
 }
 
-fn parse_initial_values(dbc_input: &str) -> HashMap<(u32, String), f64> {
-    let re_sig_val =
-        Regex::new(r#"BA_\s+"GenSigStartValue"\s+SG_\s+(\d+)\s+([^\s]+)\s+([^;]+);"#).unwrap();
+fn parse_initial_values(dbc_input: &str, name: &str) -> Result<HashMap<(u32, String), f64>, Error> {
+    let re_sig_val = name_regex(
+        &format!(
+            r#"BA_\s+"GenSigStartValue"\s+SG_\s+(\d+)\s+({})\s+([^;]+);"#,
+            name
+        ),
+        name,
+    )?;
     let mut initial_values: HashMap<(u32, String), f64> = HashMap::new();
 
     for cap in re_sig_val.captures_iter(dbc_input) {
         if let Ok(message_id) = cap[1].parse::<u32>() {
             let signal_name = cap[2].to_string();
-            if let Ok(value) = cap[3].trim().parse::<f64>() {
+            // Some tools write the raw start value in hex
+            let token = cap[3].trim();
+            let value = match token
+                .strip_prefix("0x")
+                .or_else(|| token.strip_prefix("0X"))
+            {
+                Some(hex) => u64::from_str_radix(hex, 16).ok().map(|raw| raw as f64),
+                None => parse_number(token).ok(),
+            };
+            if let Some(value) = value {
                 initial_values.insert((message_id, signal_name), value);
             }
         }
     }
 
-    initial_values
+    Ok(initial_values)
 }
 
-fn parse_sig_valtypes(dbc_input: &str) -> HashMap<(u32, String), u32> {
-    let re_valtype =
-        Regex::new(r#"SIG_VALTYPE_\s+(\d+)\s+([a-zA-Z0-9_]+)\s*:\s*(\d+)\s*;"#).unwrap();
+fn parse_sig_valtypes(dbc_input: &str, name: &str) -> Result<HashMap<(u32, String), u32>, Error> {
+    let re_valtype = name_regex(
+        &format!(r#"SIG_VALTYPE_\s+(\d+)\s+({})\s*:\s*(\d+)\s*;"#, name),
+        name,
+    )?;
     let mut map = HashMap::new();
     for cap in re_valtype.captures_iter(dbc_input) {
         if let (Ok(msg_id), Ok(val_type)) = (cap[1].parse::<u32>(), cap[3].parse::<u32>()) {
             map.insert((msg_id, cap[2].to_string()), val_type);
         }
     }
-    map
+    Ok(map)
+}
+
+fn parse_multiplexor_signals(
+    dbc_input: &str,
+    name: &str,
+) -> Result<HashMap<(u32, String), String>, Error> {
+    let re_mul_val = name_regex(
+        &format!(r#"SG_MUL_VAL_\s+(\d+)\s+({})\s+({})\s+[^;]*;"#, name, name),
+        name,
+    )?;
+    let mut map = HashMap::new();
+    for cap in re_mul_val.captures_iter(dbc_input) {
+        if let Ok(msg_id) = cap[1].parse::<u32>() {
+            map.insert((msg_id, cap[2].to_string()), cap[3].to_string());
+        }
+    }
+    Ok(map)
 }
 
-fn parse_value_descriptions(dbc_input: &str) -> HashMap<(u32, String), HashMap<u64, String>> {
-    let re_val = Regex::new(r#"VAL_\s+(\d+)\s+(\w+)\s+(.+?);"#).unwrap();
-    let mut value_descriptions: HashMap<(u32, String), HashMap<u64, String>> = HashMap::new();
+fn parse_value_descriptions(
+    dbc_input: &str,
+    name: &str,
+) -> Result<SignalMap<HashMap<i64, String>>, Error> {
+    // Some files omit the closing `;`, so the end of the line terminates the list as well.
+    // Descriptions may contain `;` and `\"` escapes.
+    let re_val = name_regex(
+        &format!(
+            r#"(?m)VAL_\s+(\d+)\s+({})\s+((?:"(?:[^"\\\n]|\\.)*"|[^";\n])+)(?:;|$)"#,
+            name
+        ),
+        name,
+    )?;
+    let mut value_descriptions: HashMap<(u32, String), HashMap<i64, String>> = HashMap::new();
 
     for cap in re_val.captures_iter(dbc_input) {
         if let Ok(message_id) = cap[1].parse::<u32>() {
             let signal_name = cap[2].to_string();
-            let values_str = &cap[3];
+            let signal_values = parse_value_pairs(&cap[3]);
+            if !signal_values.is_empty() {
+                value_descriptions.insert((message_id, signal_name), signal_values);
+            }
+        }
+    }
 
-            let mut signal_values = HashMap::new();
-            let re_value_pair = Regex::new(r#"(\d+)\s+"([^"]+)""#).unwrap();
+    Ok(value_descriptions)
+}
 
-            for value_cap in re_value_pair.captures_iter(values_str) {
-                if let Ok(value) = value_cap[1].parse::<u64>() {
-                    let description = value_cap[2].to_string();
-                    signal_values.insert(value, description);
-                }
+/// Returns the `BA_DEF_` definitions together with the `BA_DEF_DEF_` defaults left without one
+fn parse_attribute_definitions(
+    dbc_input: &str,
+) -> (Vec<AttributeDefinition>, HashMap<String, AttributeValue>) {
+    let re_def = Regex::new(
+        r#"BA_DEF_\s+(?:(BU_|BO_|SG_|EV_)\s+)?"(\w+)"\s+(INT|HEX|FLOAT|STRING|ENUM)\s*([^;]*);"#,
+    )
+    .unwrap();
+    let mut defaults = parse_attribute_defaults(dbc_input);
+    let mut definitions = Vec::new();
+
+    for cap in re_def.captures_iter(dbc_input) {
+        let object_type = match cap.get(1).map(|m| m.as_str()) {
+            Some("BU_") => AttributeObjectType::Node,
+            Some("BO_") => AttributeObjectType::Message,
+            Some("SG_") => AttributeObjectType::Signal,
+            Some("EV_") => AttributeObjectType::EnvironmentVariable,
+            _ => AttributeObjectType::Network,
+        };
+        definitions.push(AttributeDefinition {
+            name: cap[2].to_string(),
+            object_type,
+            value_type: parse_attribute_value_type(&cap[3], &cap[4]),
+            default: defaults.remove(&cap[2]),
+        });
+    }
+    (definitions, defaults)
+}
+
+/// Stands in for the missing `BA_DEF_` of a `BA_DEF_DEF_`, typed after its value
+fn implied_definition(name: String, default: AttributeValue) -> AttributeDefinition {
+    let value_type = match default {
+        AttributeValue::Int(_) => AttributeValueType::Int(0, 0),
+        AttributeValue::Float(_) => AttributeValueType::Float(0.0, 0.0),
+        AttributeValue::String(_) => AttributeValueType::String,
+    };
+    AttributeDefinition {
+        name,
+        object_type: AttributeObjectType::Network,
+        value_type,
+        default: Some(default),
+    }
+}
+
+/// Parses the value type keyword of a `BA_DEF_`/`BA_DEF_REL_` and the parameters following it
+fn parse_attribute_value_type(kind: &str, params: &str) -> AttributeValueType {
+    let bounds: Vec<&str> = params.split_whitespace().collect();
+    let int_bounds = || {
+        (
+            bounds.first().and_then(|v| v.parse().ok()).unwrap_or(0),
+            bounds.get(1).and_then(|v| v.parse().ok()).unwrap_or(0),
+        )
+    };
+    match kind {
+        "INT" => {
+            let (min, max) = int_bounds();
+            AttributeValueType::Int(min, max)
+        }
+        "HEX" => {
+            let (min, max) = int_bounds();
+            AttributeValueType::Hex(min, max)
+        }
+        "FLOAT" => AttributeValueType::Float(
+            bounds.first().and_then(|v| v.parse().ok()).unwrap_or(0.0),
+            bounds.get(1).and_then(|v| v.parse().ok()).unwrap_or(0.0),
+        ),
+        "ENUM" => AttributeValueType::Enum(
+            params
+                .split(',')
+                .map(|s| s.trim().trim_matches('"').to_string())
+                .collect(),
+        ),
+        _ => AttributeValueType::String,
+    }
+}
+
+fn parse_relation_attribute_definitions(dbc_input: &str) -> Vec<RelationAttributeDefinition> {
+    let re_def = Regex::new(
+        r#"BA_DEF_REL_\s+(BU_BO_REL_|BU_SG_REL_|BU_EV_REL_)\s+"(\w+)"\s+(INT|HEX|FLOAT|STRING|ENUM)\s*([^;]*);"#,
+    )
+    .unwrap();
+    let re_default = Regex::new(r#"BA_DEF_DEF_REL_\s+"(\w+)"\s+("[^"]*"|[^;]+);"#).unwrap();
+    let mut defaults: HashMap<String, AttributeValue> = re_default
+        .captures_iter(dbc_input)
+        .filter_map(|cap| Some((cap[1].to_string(), parse_attribute_value(&cap[2])?)))
+        .collect();
+    let mut definitions = Vec::new();
+
+    for cap in re_def.captures_iter(dbc_input) {
+        let relation_type = match &cap[1] {
+            "BU_BO_REL_" => RelationType::NodeMessage,
+            "BU_SG_REL_" => RelationType::NodeSignal,
+            _ => RelationType::NodeEnvVar,
+        };
+
+        definitions.push(RelationAttributeDefinition {
+            name: cap[2].to_string(),
+            relation_type,
+            value_type: parse_attribute_value_type(&cap[3], &cap[4]),
+            default: defaults.remove(&cap[2]),
+        });
+    }
+    definitions
+}
+
+/// Parses the `value "description"` pairs of a `VAL_` or `VAL_TABLE_` statement
+fn parse_value_pairs(values_str: &str) -> HashMap<i64, String> {
+    // A pair is a number directly followed by a string, so the count some exporters write
+    // before the pairs (`VAL_ 1 Sig 3 0 "A" 1 "B" 2 "C";`) is skipped
+    let re_value_pair = Regex::new(r#"(-?\d+)\s+"((?:[^"\\]|\\.)*)""#).unwrap();
+    let mut values = HashMap::new();
+
+    for value_cap in re_value_pair.captures_iter(values_str) {
+        // Keys above i64::MAX are kept by their 64 bit pattern
+        let value = value_cap[1]
+            .parse::<i64>()
+            .or_else(|_| value_cap[1].parse::<u64>().map(|value| value as i64));
+        if let Ok(value) = value {
+            values.insert(value, unescape(&value_cap[2]));
+        }
+    }
+    values
+}
+
+fn parse_value_tables(dbc_input: &str, name: &str) -> Result<Vec<ValueTable>, Error> {
+    // Anchored to the line start so the `VAL_TABLE_` entry of the `NS_` list is not taken as a table
+    let re_table = name_regex(
+        &format!(
+            r#"(?m)^[ \t]*VAL_TABLE_[ \t]+({})\s+((?:"(?:[^"\\\n]|\\.)*"|[^";\n])*)(?:;|$)"#,
+            name
+        ),
+        name,
+    )?;
+
+    Ok(re_table
+        .captures_iter(dbc_input)
+        .map(|cap| ValueTable {
+            name: cap[1].to_string(),
+            values: parse_value_pairs(&cap[2]),
+        })
+        .collect())
+}
+
+/// Returns the ID of the message written as `id` in its `BO_` line, as reclassified by
+/// `VFrameFormat`, or classifies `id` on its own when no such message exists
+fn resolve_message_id(message_ids: &HashMap<u32, MessageID>, id: u32) -> MessageID {
+    message_ids
+        .get(&id)
+        .copied()
+        .unwrap_or_else(|| MessageID::from_dbc(id))
+}
+
+fn parse_signal_groups(
+    dbc_input: &str,
+    name: &str,
+    message_ids: &HashMap<u32, MessageID>,
+) -> Result<Vec<SignalGroup>, Error> {
+    let re_group = name_regex(
+        &format!(r#"SIG_GROUP_\s+(\d+)\s+({})\s+(\d+)\s*:([^;]*);"#, name),
+        name,
+    )?;
+    let mut groups = Vec::new();
+
+    for cap in re_group.captures_iter(dbc_input) {
+        if let (Ok(id), Ok(repetitions)) = (cap[1].parse::<u32>(), cap[3].parse::<u32>()) {
+            groups.push(SignalGroup {
+                message_id: resolve_message_id(message_ids, id),
+                name: cap[2].to_string(),
+                repetitions,
+                signals: cap[4].split_whitespace().map(str::to_string).collect(),
+            });
+        }
+    }
+    Ok(groups)
+}
+
+fn parse_relation_attributes(
+    dbc_input: &str,
+    name: &str,
+    message_ids: &HashMap<u32, MessageID>,
+) -> Result<Vec<RelationAttribute>, Error> {
+    let re_rel = name_regex(
+        &format!(
+            r#"BA_REL_\s+"(\w+)"\s+(?:BU_BO_REL_\s+(\w+)\s+(\d+)|BU_SG_REL_\s+(\w+)\s+SG_\s+(\d+)\s+({})|BU_EV_REL_\s+(\w+)\s+(\w+))\s+("[^"]*"|[^;]+);"#,
+            name
+        ),
+        name,
+    )?;
+    let mut attributes = Vec::new();
+
+    for cap in re_rel.captures_iter(dbc_input) {
+        let Some(value) = parse_attribute_value(&cap[9]) else {
+            continue;
+        };
+        let relation = if let (Some(node), Some(id)) = (cap.get(2), cap.get(3)) {
+            id.as_str().parse::<u32>().ok().map(|id| {
+                (
+                    node,
+                    RelationTarget::Message(resolve_message_id(message_ids, id)),
+                )
+            })
+        } else if let (Some(node), Some(id), Some(signal)) = (cap.get(4), cap.get(5), cap.get(6)) {
+            id.as_str().parse::<u32>().ok().map(|id| {
+                let target = RelationTarget::Signal(
+                    resolve_message_id(message_ids, id),
+                    signal.as_str().to_string(),
+                );
+                (node, target)
+            })
+        } else if let (Some(node), Some(env_var)) = (cap.get(7), cap.get(8)) {
+            Some((node, RelationTarget::EnvVar(env_var.as_str().to_string())))
+        } else {
+            None
+        };
+
+        if let Some((node, target)) = relation {
+            attributes.push(RelationAttribute {
+                name: cap[1].to_string(),
+                node: node.as_str().to_string(),
+                target,
+                value,
+            });
+        }
+    }
+    Ok(attributes)
+}
+
+/// Parses a `BA_`/`BA_DEF_DEF_` value token: a quoted string or a number
+fn parse_attribute_value(token: &str) -> Option<AttributeValue> {
+    let token = token.trim();
+    if let Some(text) = token.strip_prefix('"') {
+        Some(AttributeValue::String(
+            text.trim_end_matches('"').to_string(),
+        ))
+    } else if let Ok(value) = token.parse::<i64>() {
+        Some(AttributeValue::Int(value))
+    } else {
+        token.parse::<f64>().ok().map(AttributeValue::Float)
+    }
+}
+
+/// Collects every `BA_DEF_DEF_` value by attribute name
+fn parse_attribute_defaults(dbc_input: &str) -> HashMap<String, AttributeValue> {
+    let re_default = Regex::new(r#"BA_DEF_DEF_\s+"(\w+)"\s+("[^"]*"|[^;]+);"#).unwrap();
+    re_default
+        .captures_iter(dbc_input)
+        .filter_map(|cap| Some((cap[1].to_string(), parse_attribute_value(&cap[2])?)))
+        .collect()
+}
+
+/// Returns the `BA_DEF_DEF_` value of the named attribute
+fn attribute_default<'a>(
+    attribute_definitions: &'a [AttributeDefinition],
+    name: &str,
+) -> Option<&'a AttributeValue> {
+    attribute_definitions
+        .iter()
+        .find(|def| def.name == name)
+        .and_then(|def| def.default.as_ref())
+}
+
+fn parse_node_attributes(dbc_input: &str) -> HashMap<String, HashMap<String, AttributeValue>> {
+    let re_attr = Regex::new(r#"BA_\s+"(\w+)"\s+BU_\s+(\w+)\s+("[^"]*"|[^;]+);"#).unwrap();
+    let mut map: HashMap<String, HashMap<String, AttributeValue>> = HashMap::new();
+
+    for cap in re_attr.captures_iter(dbc_input) {
+        if let Some(value) = parse_attribute_value(&cap[3]) {
+            map.entry(cap[2].to_string())
+                .or_default()
+                .insert(cap[1].to_string(), value);
+        }
+    }
+    map
+}
+
+fn parse_env_var_attributes(dbc_input: &str) -> HashMap<String, HashMap<String, AttributeValue>> {
+    let re_attr = Regex::new(r#"BA_\s+"(\w+)"\s+EV_\s+(\w+)\s+("[^"]*"|[^;]+);"#).unwrap();
+    let mut map: HashMap<String, HashMap<String, AttributeValue>> = HashMap::new();
+
+    for cap in re_attr.captures_iter(dbc_input) {
+        if let Some(value) = parse_attribute_value(&cap[3]) {
+            map.entry(cap[2].to_string())
+                .or_default()
+                .insert(cap[1].to_string(), value);
+        }
+    }
+    map
+}
+
+/// Parses the `BA_` values without an object keyword, which apply to the whole database
+fn parse_network_attributes(dbc_input: &str) -> HashMap<String, AttributeValue> {
+    let re_attr =
+        Regex::new(r#"BA_\s+"(\w+)"\s+("[^"]*"|[-+]?[\d.]+(?:[eE][-+]?\d+)?)\s*;"#).unwrap();
+
+    re_attr
+        .captures_iter(dbc_input)
+        .filter_map(|cap| Some((cap[1].to_string(), parse_attribute_value(&cap[2])?)))
+        .collect()
+}
+
+fn parse_message_attributes(dbc_input: &str) -> HashMap<u32, HashMap<String, AttributeValue>> {
+    let re_attr = Regex::new(r#"BA_\s+"(\w+)"\s+BO_\s+(\d+)\s+("[^"]*"|[^;]+);"#).unwrap();
+    let mut map: HashMap<u32, HashMap<String, AttributeValue>> = HashMap::new();
+
+    for cap in re_attr.captures_iter(dbc_input) {
+        if let (Ok(id), Some(value)) = (cap[2].parse::<u32>(), parse_attribute_value(&cap[3])) {
+            map.entry(id).or_default().insert(cap[1].to_string(), value);
+        }
+    }
+    map
+}
+
+/// The kind of statement a DBC line starts, see [`classify_line`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LineKind {
+    Blank,
+    /// `VERSION`
+    Version,
+    /// `NS_`
+    NewSymbols,
+    /// `BS_`
+    BitTiming,
+    /// `BU_`
+    Nodes,
+    /// `VAL_TABLE_`
+    ValueTable,
+    /// `BO_`
+    Message,
+    /// `SG_`
+    Signal,
+    /// `BO_TX_BU_`
+    Transmitters,
+    /// `EV_`
+    EnvVar,
+    /// `ENVVAR_DATA_`
+    EnvVarData,
+    /// `CM_`
+    Comment,
+    /// `BA_DEF_`
+    AttributeDefinition,
+    /// `BA_DEF_DEF_`
+    AttributeDefault,
+    /// `BA_`
+    Attribute,
+    /// `BA_DEF_REL_`
+    RelationAttributeDefinition,
+    /// `BA_DEF_DEF_REL_`
+    RelationAttributeDefault,
+    /// `BA_REL_`
+    RelationAttribute,
+    /// `VAL_`
+    ValueDescription,
+    /// `SIG_VALTYPE_`
+    SignalValueType,
+    /// `SIG_GROUP_`
+    SignalGroup,
+    /// `SG_MUL_VAL_`
+    ExtendedMultiplexing,
+    /// Any other keyword, or text without one
+    Unknown,
+}
+
+/// Classifies a line by the keyword it starts with, as recognised by the parser.
+/// Lines are looked at on their own and without allocating, so the indented symbols of an
+/// `NS_` list or the continuation of a multi-line comment are classified by their own text.
+pub fn classify_line(line: &str) -> LineKind {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return LineKind::Blank;
+    }
+    match line_keyword(trimmed) {
+        "VERSION" => LineKind::Version,
+        "NS_" => LineKind::NewSymbols,
+        "BS_" => LineKind::BitTiming,
+        "BU_" => LineKind::Nodes,
+        "VAL_TABLE_" => LineKind::ValueTable,
+        "BO_" => LineKind::Message,
+        "SG_" => LineKind::Signal,
+        "BO_TX_BU_" => LineKind::Transmitters,
+        "EV_" => LineKind::EnvVar,
+        "ENVVAR_DATA_" => LineKind::EnvVarData,
+        "CM_" => LineKind::Comment,
+        "BA_DEF_" => LineKind::AttributeDefinition,
+        "BA_DEF_DEF_" => LineKind::AttributeDefault,
+        "BA_" => LineKind::Attribute,
+        "BA_DEF_REL_" => LineKind::RelationAttributeDefinition,
+        "BA_DEF_DEF_REL_" => LineKind::RelationAttributeDefault,
+        "BA_REL_" => LineKind::RelationAttribute,
+        "VAL_" => LineKind::ValueDescription,
+        "SIG_VALTYPE_" => LineKind::SignalValueType,
+        "SIG_GROUP_" => LineKind::SignalGroup,
+        "SG_MUL_VAL_" => LineKind::ExtendedMultiplexing,
+        _ => LineKind::Unknown,
+    }
+}
+
+/// Returns the keyword a trimmed statement starts with, ending at whitespace or `:`
+fn line_keyword(trimmed: &str) -> &str {
+    trimmed
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .next()
+        .unwrap_or_default()
+}
+
+/// Keywords of the statements the parser models
+const PARSED_KEYWORDS: &[&str] = &[
+    "VERSION",
+    "NS_",
+    "BS_",
+    "BU_",
+    "BO_",
+    "SG_",
+    "BO_TX_BU_",
+    "EV_",
+    "ENVVAR_DATA_",
+    "CM_",
+    "BA_DEF_",
+    "BA_DEF_DEF_",
+    "BA_",
+    "BA_DEF_REL_",
+    "BA_DEF_DEF_REL_",
+    "BA_REL_",
+    "VAL_",
+    "SIG_VALTYPE_",
+    "SG_MUL_VAL_",
+    "SIG_GROUP_",
+    "VAL_TABLE_",
+];
+
+/// Collects the statements no parser takes into the model: those starting with a keyword the
+/// parser does not know, and known statements it skips (see [`is_modelled`]).
+/// A statement continues onto the next line while it has an unterminated string.
+fn parse_unparsed_statements(
+    dbc_input: &str,
+    attribute_definitions: &[AttributeDefinition],
+) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut pending: Option<String> = None;
+    let mut in_new_symbols = false;
+    let mut keep = |statement: String| {
+        if !is_modelled(&statement, attribute_definitions) {
+            statements.push(statement);
+        }
+    };
+
+    for line in dbc_input.lines() {
+        if let Some(mut statement) = pending.take() {
+            statement.push('\n');
+            statement.push_str(line);
+            if has_open_string(&statement) {
+                pending = Some(statement);
+            } else {
+                keep(statement);
             }
+            continue;
+        }
 
-            if !signal_values.is_empty() {
-                value_descriptions.insert((message_id, signal_name), signal_values);
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        // The NS_ list continues with indented symbol names
+        if in_new_symbols && line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        in_new_symbols = false;
+
+        let keyword = line_keyword(trimmed);
+        if keyword == "NS_" {
+            in_new_symbols = true;
+        }
+        if has_open_string(trimmed) {
+            pending = Some(trimmed.to_string());
+        } else {
+            keep(trimmed.to_string());
+        }
+    }
+
+    if let Some(statement) = pending {
+        keep(statement);
+    }
+    statements
+}
+
+/// Returns whether a parser takes the statement into the model. Some statements with a known
+/// keyword are not modelled: network and `EV_` comments, the `VAL_` lists of environment
+/// variables and `BA_DEF_DEF_` defaults without a `BA_DEF_`.
+fn is_modelled(statement: &str, attribute_definitions: &[AttributeDefinition]) -> bool {
+    let keyword = line_keyword(statement);
+    let mut tokens = statement[keyword.len()..].split_whitespace();
+    match keyword {
+        "CM_" => matches!(tokens.next(), Some("BU_" | "BO_" | "SG_")),
+        "VAL_" => tokens.next().is_some_and(|id| id.parse::<u32>().is_ok()),
+        "BA_DEF_DEF_" => tokens.next().is_some_and(|name| {
+            let name = name.trim_end_matches(';').trim_matches('"');
+            attribute_definitions.iter().any(|def| def.name == name)
+        }),
+        _ => PARSED_KEYWORDS.contains(&keyword),
+    }
+}
+
+/// Returns whether the text ends inside a quoted string, honouring `\"` escapes
+fn has_open_string(text: &str) -> bool {
+    let mut open = false;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if open => {
+                chars.next();
             }
+            '"' => open = !open,
+            _ => {}
+        }
+    }
+    open
+}
+
+fn parse_version(dbc_input: &str) -> String {
+    let re_version = Regex::new(r#"VERSION\s+"([^"]*)""#).unwrap();
+    re_version
+        .captures(dbc_input)
+        .map(|cap| cap[1].to_string())
+        .unwrap_or_default()
+}
+
+fn parse_signal_attributes(
+    dbc_input: &str,
+    name: &str,
+) -> Result<SignalMap<HashMap<String, AttributeValue>>, Error> {
+    let re_attr = name_regex(
+        &format!(
+            r#"BA_\s+"(\w+)"\s+SG_\s+(\d+)\s+({})\s+("[^"]*"|[^;]+);"#,
+            name
+        ),
+        name,
+    )?;
+    let mut map: HashMap<(u32, String), HashMap<String, AttributeValue>> = HashMap::new();
+
+    for cap in re_attr.captures_iter(dbc_input) {
+        if let (Ok(msg_id), Some(value)) = (cap[2].parse::<u32>(), parse_attribute_value(&cap[4])) {
+            map.entry((msg_id, cap[3].to_string()))
+                .or_default()
+                .insert(cap[1].to_string(), value);
         }
     }
+    Ok(map)
+}
+
+/// Resolves a yes/no attribute such as `GenMsgILSupport` from the explicit value or the definition
+/// default. Enum labels `Yes`/`No`, `True`/`False` and `On`/`Off` are accepted in any case,
+/// as are integers, where any value but 0 means yes.
+fn attribute_flag(
+    attribute_definitions: &[AttributeDefinition],
+    attributes: &HashMap<String, AttributeValue>,
+    name: &str,
+) -> Option<bool> {
+    let value = attributes
+        .get(name)
+        .or_else(|| attribute_default(attribute_definitions, name))?;
+    let label = resolve_enum_label(attribute_definitions, name, value)?;
+    match label.trim().to_ascii_lowercase().as_str() {
+        "yes" | "true" | "on" => Some(true),
+        "no" | "false" | "off" => Some(false),
+        other => other.parse::<i64>().ok().map(|value| value != 0),
+    }
+}
+
+/// Resolves an enum attribute value to its label. Values of attributes
+/// without an `ENUM` definition are returned as written.
+fn resolve_enum_label(
+    attribute_definitions: &[AttributeDefinition],
+    name: &str,
+    value: &AttributeValue,
+) -> Option<String> {
+    let variants = attribute_definitions
+        .iter()
+        .find(|def| def.name == name)
+        .and_then(|def| match &def.value_type {
+            AttributeValueType::Enum(variants) => Some(variants),
+            _ => None,
+        });
 
-    value_descriptions
+    match (variants, value) {
+        (Some(variants), AttributeValue::Int(idx)) => usize::try_from(*idx)
+            .ok()
+            .and_then(|idx| variants.get(idx))
+            .cloned(),
+        (_, value) => Some(value.to_string()),
+    }
 }
 
 fn parse_tx_methods(dbc_input: &str) -> (String, HashMap<u32, String>) {
@@ -589,17 +4872,16 @@ fn parse_tx_methods(dbc_input: &str) -> (String, HashMap<u32, String>) {
     }
 
     let mut default_method = String::new();
-    let re_default_str = Regex::new(r#"BA_DEF_DEF_\s+"GenMsgSendType"\s+"([^"]+)";"#).unwrap();
+    let re_default_str = Regex::new(r#"BA_DEF_DEF_\s+"GenMsgSendType"\s+"([^"]+)"\s*;"#).unwrap();
     if let Some(cap) = re_default_str.captures(dbc_input) {
         default_method = cap[1].to_string();
     } else {
-        let re_default_int = Regex::new(r#"BA_DEF_DEF_\s+"GenMsgSendType"\s+(\d+);"#).unwrap();
-        if let Some(cap) = re_default_int.captures(dbc_input) {
-            if let Ok(idx) = cap[1].parse::<usize>() {
-                if idx < enum_variants.len() {
-                    default_method = enum_variants[idx].clone();
-                }
-            }
+        let re_default_int = Regex::new(r#"BA_DEF_DEF_\s+"GenMsgSendType"\s+(\d+)\s*;"#).unwrap();
+        if let Some(cap) = re_default_int.captures(dbc_input)
+            && let Ok(idx) = cap[1].parse::<usize>()
+            && idx < enum_variants.len()
+        {
+            default_method = enum_variants[idx].clone();
         }
     }
 
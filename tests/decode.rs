@@ -0,0 +1,125 @@
+use rs_dbc::{Dbc, DecodeError};
+use std::collections::HashMap;
+
+const MULTIPLEXED: &str = r#"
+BO_ 1 Mux: 8 X
+ SG_ Mode M : 0|8@1+ (1,0) [0|0] "" X
+ SG_ Speed m1 : 8|16@1+ (0.5,0) [0|0] "km/h" X
+ SG_ Level m2 : 8|8@1- (1,-10) [0|0] "" X
+ SG_ State : 24|2@1+ (1,0) [0|0] "" X
+
+VAL_ 1 State 0 "Off" 1 "On";
+"#;
+
+#[test]
+fn decoders_agree_on_active_signals() {
+    let dbc = Dbc::try_from(MULTIPLEXED).unwrap();
+    let msg = &dbc.messages[0];
+    let data = [1, 0x10, 0x00, 0x01, 0, 0, 0, 0];
+
+    let full = msg.decode_full(&data);
+    let names: Vec<&str> = full.iter().map(|sig| sig.name.as_str()).collect();
+    assert_eq!(names, ["Mode", "Speed", "State"]);
+    assert_eq!(full[1].raw, 16);
+    assert_eq!(full[1].physical, 8.0);
+    assert_eq!(full[1].unit, "km/h");
+    assert_eq!(full[2].description.as_deref(), Some("On"));
+
+    let ordered = msg.decode_ordered(&data);
+    let decoded = msg.decode(&data);
+    let raw = msg.decode_raw(&data);
+    assert_eq!(ordered.len(), full.len());
+    for (sig, (name, physical)) in full.iter().zip(&ordered) {
+        assert_eq!(&sig.name, name);
+        assert_eq!(sig.physical, *physical);
+        assert_eq!(decoded[name], *physical);
+        assert_eq!(raw[name], sig.raw);
+    }
+}
+
+#[test]
+fn ieee_signals_decode_their_bits() {
+    let input = r#"
+BO_ 1 Floats: 16 X
+ SG_ Single : 0|32@1- (1,0) [0|0] "" X
+ SG_ Scaled : 32|32@1- (2,1) [0|0] "" X
+ SG_ Double : 64|64@1- (1,0) [0|0] "" X
+
+SIG_VALTYPE_ 1 Single : 1;
+SIG_VALTYPE_ 1 Scaled : 1;
+SIG_VALTYPE_ 1 Double : 2;
+"#;
+    let dbc = Dbc::try_from(input).unwrap();
+    let mut data = Vec::new();
+    data.extend_from_slice(&0x4228_0000u32.to_le_bytes());
+    data.extend_from_slice(&0x4228_0000u32.to_le_bytes());
+    data.extend_from_slice(&(-1.25f64).to_bits().to_le_bytes());
+
+    let decoded = dbc.messages[0].decode(&data);
+    assert_eq!(decoded["Single"], 42.0);
+    assert_eq!(decoded["Scaled"], 85.0);
+    assert_eq!(decoded["Double"], -1.25);
+}
+
+#[test]
+fn short_frames_leave_out_missing_signals() {
+    let input = r#"
+BO_ 1 Msg: 8 X
+ SG_ First : 0|16@1+ (1,0) [0|0] "" X
+ SG_ Edge : 24|8@1+ (1,0) [0|0] "" X
+ SG_ Last : 48|16@1+ (1,0) [0|0] "" X
+"#;
+    let dbc = Dbc::try_from(input).unwrap();
+    let decoded = dbc.messages[0].decode(&[0x34, 0x12, 0, 7]);
+    assert_eq!(decoded.len(), 2);
+    assert_eq!(decoded["First"], 0x1234 as f64);
+    assert_eq!(decoded["Edge"], 7.0);
+    assert!(!decoded.contains_key("Last"));
+}
+
+#[test]
+fn try_encode_rejects_overlapping_signals() {
+    let input = r#"
+BO_ 1 Msg: 8 X
+ SG_ Low : 0|8@1+ (1,0) [0|0] "" X
+ SG_ Wide : 4|8@1+ (1,0) [0|0] "" X
+ SG_ High : 16|8@1+ (1,0) [0|0] "" X
+"#;
+    let dbc = Dbc::try_from(input).unwrap();
+    let msg = &dbc.messages[0];
+    let values = |names: &[&str]| -> HashMap<String, f64> {
+        names.iter().map(|name| (name.to_string(), 1.0)).collect()
+    };
+
+    assert_eq!(
+        msg.try_encode(&values(&["Low", "Wide"])),
+        Err(DecodeError::Overlap {
+            a: "Low".to_string(),
+            b: "Wide".to_string(),
+        })
+    );
+    let values = values(&["Low", "High"]);
+    assert_eq!(msg.try_encode(&values), Ok(msg.encode(&values)));
+}
+
+#[test]
+fn signals_beyond_eight_bytes_of_an_fd_frame() {
+    let input = r#"
+BO_ 1 Fd: 64 X
+ SG_ Mid : 60|16@1+ (1,0) [0|0] "" X
+ SG_ Last : 496|16@1+ (1,0) [0|0] "" X
+"#;
+    let dbc = Dbc::try_from(input).unwrap();
+    let msg = &dbc.messages[0];
+    let mut data = [0u8; 64];
+    data[7] = 0x40;
+    data[8] = 0x23;
+    data[9] = 0x01;
+    data[62] = 0xCD;
+    data[63] = 0xAB;
+
+    let decoded = msg.decode(&data);
+    assert_eq!(decoded["Mid"], 0x1234 as f64);
+    assert_eq!(decoded["Last"], 0xABCD as f64);
+    assert_eq!(msg.encode(&decoded), data);
+}
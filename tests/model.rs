@@ -0,0 +1,74 @@
+use rs_dbc::{AttributeValue, Dbc, DbcBuilder, Message, MessageID, Signal};
+
+fn message_with(name: &str, value: AttributeValue) -> Message {
+    let mut msg = Message::new("Msg", MessageID::Standard(1), 8);
+    msg.attributes.insert(name.to_string(), value);
+    msg
+}
+
+#[test]
+fn network_management_and_diagnostic_flags() {
+    let yes = |label: &str| AttributeValue::String(label.to_string());
+    assert!(message_with("NmMessage", AttributeValue::Int(1)).is_network_management());
+    assert!(message_with("NmMessage", yes("On")).is_network_management());
+    assert!(!message_with("NmMessage", yes("No")).is_network_management());
+    assert!(!message_with("NmMessage", AttributeValue::Int(0)).is_network_management());
+    assert!(message_with("DiagResponse", yes("Yes")).is_diagnostic());
+    assert!(!Message::new("Msg", MessageID::Standard(1), 8).is_diagnostic());
+}
+
+#[test]
+fn corrupt_message_size_is_capped() {
+    let mut msg = Message::new("Msg", MessageID::Standard(1), 4_294_967_295);
+    msg.signals.push(Signal::new("Sig", 0, 8));
+    assert_eq!(msg.payload_len(), 64);
+    assert_eq!(msg.layout_grid().len(), 64);
+    let values = [("Sig".to_string(), 3.0)].into_iter().collect();
+    let data = msg.encode(&values);
+    assert_eq!(data.len(), 64);
+    assert_eq!(data[0], 3);
+    assert_eq!(msg.try_encode(&values).unwrap().len(), 64);
+}
+
+#[test]
+fn bus_load_counts_fd_frames() {
+    let mut classic = Message::new("Classic", MessageID::Standard(1), 8);
+    classic.cycle_time = 10;
+    let mut fd = Message::new("Fd", MessageID::Standard(2), 64);
+    fd.cycle_time = 10;
+
+    // 44 frame bits plus the payload, then 24 stuff bits and the interframe space per frame
+    assert_eq!(classic.frame_bit_length(false), 108);
+    let dbc = DbcBuilder::new().message(classic).build();
+    assert!((dbc.estimated_bus_load(500_000) - 135.0 * 100.0 / 500_000.0).abs() < 1e-9);
+
+    // Longer than 8 bytes, so counted as FD even when asked for a classic frame
+    assert_eq!(fd.frame_bit_length(false), 576);
+    let dbc = DbcBuilder::new().message(fd).build();
+    assert!((dbc.estimated_bus_load(500_000) - 712.0 * 100.0 / 500_000.0).abs() < 1e-9);
+}
+
+#[test]
+fn resolve_id_uses_the_extended_flag() {
+    let dbc = DbcBuilder::new()
+        .message(Message::new("Std", MessageID::Standard(0x100), 8))
+        .message(Message::new("Ext", MessageID::Extended(0x100), 8))
+        .build();
+    let name = |raw: u32| dbc.resolve_id(raw).map(|msg| msg.message_name.as_str());
+    assert_eq!(name(0x100), Some("Std"));
+    assert_eq!(name(0x8000_0100), Some("Ext"));
+    assert_eq!(name(0xE000_0100), Some("Ext"));
+    assert_eq!(name(0x8000_0200), None);
+    assert_eq!(name(0x200), None);
+}
+
+#[test]
+fn bit_usage_of_corrupt_message_size_is_capped() {
+    let input = "BO_ 1 X: 4294967295 N\n SG_ Sig : 0|8@1+ (1,0) [0|0] \"\" N\n";
+    let msg = Dbc::try_from(input).unwrap().messages.remove(0);
+    assert_eq!(msg.message_size, 4_294_967_295);
+    assert_eq!(msg.bits_available(), 512);
+    assert_eq!(msg.bits_used(), 8);
+    assert_eq!(msg.free_bits(), 504);
+    assert_eq!(msg.unused_bits(), vec![8..512]);
+}
@@ -0,0 +1,532 @@
+use rs_dbc::{
+    ByteOrder, Dbc, Error, Message, MessageID, MultiplexIndicator, ParseOptions, ParseWarning,
+    Signal, ValueType,
+};
+
+fn signal_names(dbc: &Dbc, id: u16) -> Vec<&str> {
+    dbc.message_by_id(MessageID::Standard(id))
+        .unwrap()
+        .signals
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect()
+}
+
+#[test]
+fn malformed_signal_does_not_end_message_block() {
+    let input = r#"
+BO_ 1 A: 8 X
+ SG_ A1 : 0|8@1+ (1,0) [0|0] "" X
+ SG_ Broken : 8|x@1+ (1,0) [0|0] "" X
+ SG_ A2 : 16|8@1+ (1,0) [0|0] "" X
+
+BO_ 2 B: 8 X
+ SG_ B1 : 0|8@1+ (1,0) [0|0] "" X
+"#;
+    let (dbc, warnings) = Dbc::parse_with_warnings(input).unwrap();
+    assert_eq!(signal_names(&dbc, 1), ["A1", "A2"]);
+    assert_eq!(signal_names(&dbc, 2), ["B1"]);
+    assert!(warnings.is_empty(), "{warnings:?}");
+}
+
+#[test]
+fn signals_outside_a_message_block() {
+    let input = r#"
+ SG_ Early : 0|8@1+ (1,0) [0|0] "" X
+
+BO_ 1 A: 8 X
+ SG_ A1 : 8|8@1+ (1,0) [0|0] "" X
+
+CM_ BO_ 1 "Ends the block";
+ SG_ Late : 16|8@1+ (1,0) [0|0] "" X
+
+BO_ 2 B: 8 X
+ SG_ B1 : 0|8@1+ (1,0) [0|0] "" X
+"#;
+    let (dbc, warnings) = Dbc::parse_with_warnings(input).unwrap();
+    assert_eq!(signal_names(&dbc, 1), ["Early", "A1"]);
+    assert_eq!(signal_names(&dbc, 2), ["Late", "B1"]);
+    let reordered = |message_id, signal: &str| ParseWarning::ReorderedSignal {
+        message_id,
+        signal: signal.to_string(),
+    };
+    assert_eq!(warnings, [reordered(1, "Early"), reordered(2, "Late")]);
+}
+
+#[test]
+fn name_pattern_is_validated_as_embedded() {
+    let input = "BO_ 1 Msg-A: 8 X\n SG_ Sig-A : 0|8@1+ (1,0) [0|0] \"\" X\n";
+    let options = ParseOptions {
+        name_pattern: Some(r"(?x)\w+ # name".to_string()),
+        ..ParseOptions::default()
+    };
+    assert!(matches!(
+        Dbc::parse_with_options(input, &options),
+        Err(Error::NamePattern(_))
+    ));
+
+    let options = ParseOptions {
+        name_pattern: Some(r"[\w-]+".to_string()),
+        ..ParseOptions::default()
+    };
+    let dbc = Dbc::parse_with_options(input, &options).unwrap();
+    assert_eq!(signal_names(&dbc, 1), ["Sig-A"]);
+}
+
+#[test]
+fn layout_only_skips_node_comments_and_attributes() {
+    let input = r#"
+BU_: ECU1
+
+BO_ 1 A: 8 ECU1
+ SG_ A1 : 0|8@1+ (1,0) [0|0] "" ECU1
+
+CM_ BU_ ECU1 "Engine";
+BA_DEF_ BU_ "NodeLayerModules" STRING ;
+BA_ "NodeLayerModules" BU_ ECU1 "CANoeILNLVector.dll";
+"#;
+    let dbc = Dbc::parse_layout_only(input).unwrap();
+    assert_eq!(dbc.nodes.len(), 1);
+    assert_eq!(dbc.nodes[0].name, "ECU1");
+    assert_eq!(dbc.nodes[0].comment, None);
+    assert!(dbc.nodes[0].attributes.is_empty());
+
+    let dbc = Dbc::try_from(input).unwrap();
+    assert_eq!(dbc.nodes[0].comment.as_deref(), Some("Engine"));
+    assert_eq!(dbc.nodes[0].attributes.len(), 1);
+}
+
+#[test]
+fn additional_transmitters_skip_the_primary_transmitter() {
+    let input = r#"
+BO_ 1 A: 8 ECU1
+ SG_ A1 : 0|8@1+ (1,0) [0|0] "" ECU2
+
+BO_TX_BU_ 1 : ECU1,ECU2,Vector__XXX,ECU2;
+"#;
+    let dbc = Dbc::try_from(input).unwrap();
+    let msg = &dbc.messages[0];
+    assert_eq!(msg.transmitters, ["ECU2"]);
+    assert_eq!(msg.senders(), ["ECU1", "ECU2"]);
+    assert!(dbc.to_dbc_string().contains("BO_TX_BU_ 1 : ECU1,ECU2;"));
+}
+
+#[test]
+fn default_without_definition_applies() {
+    let input = r#"
+BO_ 1 A: 8 X
+ SG_ A1 : 0|8@1+ (1,0) [0|0] "" X
+
+BO_ 2 B: 8 X
+ SG_ B1 : 0|8@1+ (1,0) [0|0] "" X
+
+BA_DEF_DEF_ "GenMsgCycleTime" 100;
+BA_ "GenMsgCycleTime" BO_ 2 20;
+"#;
+    let dbc = Dbc::try_from(input).unwrap();
+    assert_eq!(dbc.messages[0].cycle_time_opt, Some(100));
+    assert_eq!(dbc.messages[1].cycle_time_opt, Some(20));
+    assert!(dbc.attribute_definitions.is_empty());
+}
+
+#[test]
+fn negative_and_exponent_start_values() {
+    let input = r#"
+BO_ 1 A: 8 X
+ SG_ Temperature : 0|8@1- (1,0) [-40|87] "degC" X
+ SG_ Pressure : 8|16@1+ (0.1,0) [0|0] "kPa" X
+
+BA_DEF_ SG_ "GenSigStartValue" FLOAT -1e9 1e9;
+BA_ "GenSigStartValue" SG_ 1 Temperature -40;
+BA_ "GenSigStartValue" SG_ 1 Pressure 1.5E2;
+"#;
+    let dbc = Dbc::try_from(input).unwrap();
+    let signals = &dbc.messages[0].signals;
+    assert_eq!(signals[0].initial_value, -40.0);
+    assert_eq!(signals[0].vector_initial_value(), -40.0);
+    assert_eq!(signals[1].initial_value, 150.0);
+    assert_eq!(signals[1].vector_initial_value(), 15.0);
+}
+
+#[test]
+fn message_without_transmitter() {
+    let input = "BO_ 256 Msg: 8 \n SG_ Sig : 0|8@1+ (1,0) [0|0] \"\" X\n";
+    let dbc = Dbc::try_from(input).unwrap();
+    let msg = &dbc.messages[0];
+    assert_eq!(msg.message_name, "Msg");
+    assert_eq!(msg.message_size, 8);
+    assert_eq!(msg.transmitter, "Vector__XXX");
+    assert_eq!(msg.transmitter(), "No Transmitter");
+    assert_eq!(signal_names(&dbc, 256), ["Sig"]);
+}
+
+fn first_signal(input: &str) -> Signal {
+    Dbc::try_from(input).unwrap().messages[0].signals[0].clone()
+}
+
+#[test]
+fn space_separated_receivers() {
+    let sig = first_signal("BO_ 1 A: 8 X\n SG_ X : 0|8@1+ (1,0) [0|0] \"\" ECU1 ECU2\n");
+    assert_eq!(sig.receivers, ["ECU1", "ECU2"]);
+}
+
+#[test]
+fn multi_line_comments() {
+    let input = "BU_: ECU1\n\nBO_ 1 A: 8 ECU1\n SG_ S : 0|8@1+ (1,0) [0|0] \"\" ECU1\n\n\
+        CM_ BU_ ECU1 \"Engine\ncontrol unit\";\n\
+        CM_ BO_ 1 \"First line\r\nsecond line\";\n\
+        CM_ SG_ 1 S \"Speed\nin \\\"km/h\\\"\";\n";
+    let dbc = Dbc::try_from(input).unwrap();
+    assert_eq!(
+        dbc.nodes[0].comment.as_deref(),
+        Some("Engine\ncontrol unit")
+    );
+    assert_eq!(
+        dbc.messages[0].comment.as_deref(),
+        Some("First line\r\nsecond line")
+    );
+    assert_eq!(
+        dbc.messages[0].signals[0].comment.as_deref(),
+        Some("Speed\nin \"km/h\"")
+    );
+}
+
+#[test]
+fn whitespace_around_byte_order_and_sign() {
+    let sig = first_signal("BO_ 1 A: 8 X\n SG_ S : 16|8 @ 0 - (1,0) [0|0] \"\" X\n");
+    assert_eq!(sig.start_bit, 16);
+    assert_eq!(sig.signal_size, 8);
+    assert_eq!(sig.byte_order, ByteOrder::Motorola);
+    assert_eq!(sig.value_type, ValueType::Signed);
+}
+
+#[test]
+fn hex_message_ids() {
+    let input = "BO_ 0x100 Msg: 8 ECU\n SG_ S : 0|8@1+ (1,0) [0|0] \"\" ECU\n";
+    let dbc = Dbc::try_from(input).unwrap();
+    let msg = &dbc.messages[0];
+    assert_eq!(msg.message_id, MessageID::Standard(0x100));
+    assert_eq!(msg.message_name, "Msg");
+    assert_eq!(msg.message_size, 8);
+    assert_eq!(msg.transmitter, "ECU");
+    assert_eq!(signal_names(&dbc, 0x100), ["S"]);
+}
+
+#[test]
+fn value_descriptions_without_semicolon() {
+    let input = "BO_ 1 A: 8 X\n SG_ S : 0|8@1+ (1,0) [0|0] \"\" X\n\nVAL_ 1 S 1 \"On\" 0 \"Off\"\n";
+    let sig = first_signal(input);
+    assert_eq!(sig.value_descriptions.len(), 2);
+    assert_eq!(sig.value_descriptions[&1], "On");
+    assert_eq!(sig.value_descriptions[&0], "Off");
+}
+
+#[test]
+fn loosely_spaced_bit_spec() {
+    let tight = first_signal("BO_ 1 A: 8 X\n SG_ S : 0|8@1+ (1,0) [0|0] \"\" X\n");
+    let loose = first_signal("BO_ 1 A: 8 X\n SG_ S : 0 | 8 @ 1 + (1,0) [0|0] \"\" X\n");
+    assert_eq!(loose, tight);
+    assert_eq!(tight.signal_size, 8);
+}
+
+#[test]
+fn vector_placeholder_is_dropped_from_mixed_receivers() {
+    let sig = first_signal("BO_ 1 A: 8 X\n SG_ S : 0|8@1+ (1,0) [0|0] \"\" ECU1,Vector__XXX\n");
+    assert_eq!(sig.receivers, ["ECU1"]);
+}
+
+#[test]
+fn nested_multiplexor_token() {
+    let input = r#"
+BO_ 1 A: 8 X
+ SG_ Mux M : 0|8@1+ (1,0) [0|0] "" X
+ SG_ SubMux m2M : 8|8@1+ (1,0) [0|0] "" X
+ SG_ Value m5 : 16|8@1+ (1,0) [0|0] "" X
+
+SG_MUL_VAL_ 1 SubMux Mux 2-2;
+SG_MUL_VAL_ 1 Value SubMux 5-5;
+"#;
+    let dbc = Dbc::try_from(input).unwrap();
+    let signals = &dbc.messages[0].signals;
+    assert_eq!(
+        signals[1].multiplexer_type,
+        MultiplexIndicator::MultiplexedMultiplexer(2)
+    );
+    assert!(signals[1].is_multiplexed() && signals[1].is_multiplexor());
+    assert_eq!(signals[1].multiplexor_signal.as_deref(), Some("Mux"));
+    assert_eq!(signals[2].multiplexor_signal.as_deref(), Some("SubMux"));
+
+    let decoded = dbc.messages[0].decode(&[2, 5, 9, 0, 0, 0, 0, 0]);
+    assert_eq!(decoded["Value"], 9.0);
+    let decoded = dbc.messages[0].decode(&[1, 5, 9, 0, 0, 0, 0, 0]);
+    assert!(!decoded.contains_key("SubMux") && !decoded.contains_key("Value"));
+}
+
+#[test]
+fn escaped_quotes_in_value_descriptions() {
+    let input = "BO_ 1 A: 8 X\n SG_ S : 0|8@1+ (1,0) [0|0] \"\" X\n\n\
+        VAL_ 1 S 2 \"Pressure \\\"High\\\"\" 1 \"a;b\" 0 \"Low\" ;\n";
+    let sig = first_signal(input);
+    assert_eq!(sig.value_descriptions[&2], "Pressure \"High\"");
+    assert_eq!(sig.value_descriptions[&1], "a;b");
+    assert_eq!(sig.value_descriptions[&0], "Low");
+}
+
+#[test]
+fn tab_separated_input() {
+    let input = "BU_:\tX\tY\n\n\
+        BO_\t1\tA:\t8\tX\n\
+        \tSG_\tS\t:\t8|16@0-\t(0.5,-1)\t[-100|100]\t\"km/h\"\tX,Y\n\
+        \tSG_\tMux\tM\t:\t0|4@1+\t(1,0)\t[0|0]\t\"\"\tY\n\
+        \tSG_\tCase\tm1\t:\t4|4@1+\t(1,0)\t[0|0]\t\"\"\tY\n\n\
+        BO_\t2\tB:\t4\tY\n\
+        \tSG_\tT\t:\t0|8@1+\t(1,0)\t[0|0]\t\"\"\tX\n\n\
+        BA_DEF_\tBO_\t\"GenMsgCycleTime\"\tINT\t0\t65535;\n\
+        BA_DEF_\tBO_\t\"GenMsgSendType\"\tENUM\t\"Cyclic\",\"Event\";\n\
+        BA_DEF_DEF_\t\"GenMsgCycleTime\"\t50\t;\n\
+        BA_DEF_DEF_\t\"GenMsgSendType\"\t\"Event\"\t;\n\
+        BA_\t\"GenMsgCycleTime\"\tBO_\t1\t100;\n";
+    let dbc = Dbc::try_from(input).unwrap();
+    let names: Vec<&str> = dbc.nodes.iter().map(|node| node.name.as_str()).collect();
+    assert_eq!(names, ["X", "Y"]);
+    assert_eq!(signal_names(&dbc, 1), ["S", "Mux", "Case"]);
+    assert_eq!(signal_names(&dbc, 2), ["T"]);
+
+    let msg = &dbc.messages[0];
+    assert_eq!((msg.message_size, msg.transmitter.as_str()), (8, "X"));
+    let sig = &msg.signals[0];
+    assert_eq!((sig.start_bit, sig.signal_size), (8, 16));
+    assert_eq!(sig.byte_order, ByteOrder::Motorola);
+    assert_eq!(sig.value_type, ValueType::Signed);
+    assert_eq!((sig.factor, sig.offset), (0.5, -1.0));
+    assert_eq!((sig.min, sig.max), (-100.0, 100.0));
+    assert_eq!(sig.unit, "km/h");
+    assert_eq!(sig.receivers, ["X", "Y"]);
+    assert_eq!(
+        msg.signals[1].multiplexer_type,
+        MultiplexIndicator::Multiplexer
+    );
+    assert_eq!(
+        msg.signals[2].multiplexer_type,
+        MultiplexIndicator::Multiplexed(1)
+    );
+
+    assert_eq!(msg.cycle_time, 100);
+    assert_eq!(msg.tx_method, "Event");
+    assert_eq!(dbc.messages[1].cycle_time, 50);
+}
+
+#[test]
+fn float_bounds() {
+    let sig = first_signal("BO_ 1 A: 8 X\n SG_ S : 0|32@1- (1,0) [-3.4e38|3.4e38] \"\" X\n");
+    assert_eq!(sig.min, -3.4e38);
+    assert_eq!(sig.max, 3.4e38);
+
+    let sig = first_signal(
+        "BO_ 1 A: 8 X\n SG_ S : 0|64@1- (1,0) [-1.79769313486232E+308|1.#INF] \"\" X\n",
+    );
+    assert_eq!(sig.min, -f64::MAX);
+    assert_eq!(sig.max, f64::INFINITY);
+}
+
+#[test]
+fn dummy_node_vector_placeholders() {
+    let input = "BU_: ECU1\n\nBO_ 1 A: 8 DUMMY_NODE_VECTOR0\n \
+        SG_ S : 0|8@1+ (1,0) [0|0] \"\" DUMMY_NODE_VECTOR0\n\n\
+        BO_TX_BU_ 1 : DUMMY_NODE_VECTOR1,ECU1;\n";
+    let dbc = Dbc::try_from(input).unwrap();
+    let msg = &dbc.messages[0];
+    assert!(msg.signals[0].receivers.is_empty());
+    assert_eq!(msg.transmitter, "Vector__XXX");
+    assert_eq!(msg.transmitters, ["ECU1"]);
+}
+
+#[test]
+fn count_prefixed_value_descriptions() {
+    let input = "BO_ 1 A: 8 X\n SG_ S : 0|8@1+ (1,0) [0|0] \"\" X\n\n\
+        VAL_ 1 S 3 0 \"A\" 1 \"B\" 2 \"C\";\n";
+    let sig = first_signal(input);
+    assert_eq!(sig.value_descriptions.len(), 3);
+    assert_eq!(sig.value_descriptions[&0], "A");
+    assert_eq!(sig.value_descriptions[&1], "B");
+    assert_eq!(sig.value_descriptions[&2], "C");
+}
+
+#[test]
+fn integer_and_exponent_numbers() {
+    let plain = first_signal("BO_ 1 A: 8 X\n SG_ S : 0|8@1+ (1,0) [0|255] \"\" X\n");
+    let decimal = first_signal("BO_ 1 A: 8 X\n SG_ S : 0|8@1+ (1.0,0.0) [0.0|255.0] \"\" X\n");
+    assert_eq!(plain, decimal);
+
+    let sig = first_signal("BO_ 1 A: 8 X\n SG_ S : 0|8@1+ (1E2,-5e-1) [0|2.55E+4] \"\" X\n");
+    assert_eq!(sig.factor, 100.0);
+    assert_eq!(sig.offset, -0.5);
+    assert_eq!(sig.max, 25500.0);
+}
+
+#[test]
+fn hex_start_value() {
+    let input = "BO_ 1 A: 8 X\n SG_ S : 0|8@1+ (2,0) [0|0] \"\" X\n\n\
+        BA_ \"GenSigStartValue\" SG_ 1 S 0x10;\n";
+    assert_eq!(first_signal(input).initial_value, 16.0);
+}
+
+#[test]
+fn negative_value_description_keys() {
+    let input = "BO_ 256 A: 8 X\n SG_ Gear : 0|8@1- (1,0) [-1|1] \"\" X\n\n\
+        VAL_ 256 Gear -1 \"Reverse\" 0 \"Neutral\" 1 \"Drive\";\n";
+    let sig = first_signal(input);
+    assert_eq!(sig.value_descriptions[&-1], "Reverse");
+    assert_eq!(sig.value_descriptions[&0], "Neutral");
+    assert_eq!(sig.value_descriptions[&1], "Drive");
+    assert_eq!(sig.decode_enum(&[0xFF]), Some("Reverse"));
+    assert_eq!(sig.decode_enum(&[0x01]), Some("Drive"));
+}
+
+#[test]
+fn comments_anywhere_on_a_line() {
+    let input = "BO_ 1 A: 8 X\n SG_ S : 0|8@1+ (1,0) [0|0] \"\" X\n\n\
+        \t  CM_ BO_ 1 \"Indented\";\n\
+        VAL_ 1 S 0 \"Off\" ; CM_ SG_ 1 S \"Trailing\";\n";
+    let dbc = Dbc::try_from(input).unwrap();
+    let msg = &dbc.messages[0];
+    assert_eq!(msg.comment.as_deref(), Some("Indented"));
+    assert_eq!(msg.signals[0].comment.as_deref(), Some("Trailing"));
+    assert_eq!(msg.signals[0].value_descriptions[&0], "Off");
+}
+
+#[test]
+fn space_before_message_colon() {
+    let input = "BU_: X\n\nBO_ 256 Msg : 8 X\n SG_ S : 0|8@1+ (1,0) [0|0] \"\" X\n";
+    let dbc = Dbc::try_from(input).unwrap();
+    let msg = &dbc.messages[0];
+    assert_eq!(msg.message_name, "Msg");
+    assert_eq!(msg.message_size, 8);
+    assert_eq!(msg.transmitter, "X");
+    assert_eq!(signal_names(&dbc, 256), ["S"]);
+}
+
+#[test]
+fn headerless_file() {
+    let input = "BO_ 1 A: 8 X\n SG_ S : 0|8@1+ (1,0) [0|0] \"\" X\n\n\
+        BO_ 2 B: 4 Y\n SG_ T : 0|16@1+ (1,0) [0|0] \"\" X\n";
+    let dbc = Dbc::try_from(input).unwrap();
+    assert!(dbc.version.is_empty());
+    assert!(dbc.nodes.is_empty());
+    assert_eq!(dbc.messages.len(), 2);
+    assert_eq!(signal_names(&dbc, 1), ["S"]);
+    assert_eq!(signal_names(&dbc, 2), ["T"]);
+    assert_eq!(dbc.messages[1].transmitter, "Y");
+}
+
+#[test]
+fn strict_mode_rejects_malformed_signals() {
+    let input = "BU_: X\nBO_ 1 A: 8 X\n SG_ Bad : 8|x@1+ (1,0) [0|0] \"\" X\n \
+        SG_ Good : 0|8@1+ (1,0) [0|0] \"\" X\n";
+    let strict = ParseOptions {
+        strict: true,
+        ..ParseOptions::default()
+    };
+    match Dbc::parse_with_options(input, &strict) {
+        Err(Error::MalformedSignal(line, text)) => {
+            assert_eq!(line, 3);
+            assert_eq!(text, "SG_ Bad : 8|x@1+ (1,0) [0|0] \"\" X");
+        }
+        other => panic!("expected a malformed signal, got {:?}", other),
+    }
+
+    let dbc = Dbc::try_from(input).unwrap();
+    assert_eq!(signal_names(&dbc, 1), ["Good"]);
+}
+
+#[test]
+fn trailing_comma_in_receivers() {
+    let sig = first_signal("BO_ 1 A: 8 X\n SG_ S : 0|8@1+ (1,0) [0|0] \"\" ECU1,ECU2,\n");
+    assert_eq!(sig.receivers, ["ECU1", "ECU2"]);
+}
+
+#[test]
+fn fd_length_code_as_message_size() {
+    let input = r#"
+BO_ 1 Coded: 15 X
+ SG_ S : 0|8@1+ (1,0) [0|0] "" X
+BO_ 2 Bytes: 48 X
+ SG_ T : 0|8@1+ (1,0) [0|0] "" X
+BO_ 3 Classic: 15 X
+ SG_ U : 0|8@1+ (1,0) [0|0] "" X
+
+BA_DEF_ BO_ "VFrameFormat" ENUM "StandardCAN","ExtendedCAN","reserved","J1939PG","reserved","reserved","reserved","reserved","reserved","reserved","reserved","reserved","reserved","reserved","StandardCAN_FD","ExtendedCAN_FD";
+BA_ "VFrameFormat" BO_ 1 14;
+BA_ "VFrameFormat" BO_ 2 14;
+"#;
+    let dbc = Dbc::try_from(input).unwrap();
+    let sizes: Vec<(u64, u8)> = dbc
+        .messages
+        .iter()
+        .map(|msg| (msg.message_size, msg.dlc))
+        .collect();
+    assert_eq!(sizes, [(64, 15), (48, 14), (15, 10)]);
+    assert!(dbc.messages[0].is_fd && !dbc.messages[2].is_fd);
+}
+
+#[test]
+fn legacy_signal_without_parentheses() {
+    let sig = first_signal("BO_ 1 A: 8 X\n SG_ S : 0|8@1+ 2,5 [0|0] \"\" X\n");
+    assert_eq!(sig.factor, 2.0);
+    assert_eq!(sig.offset, 5.0);
+    assert_eq!(sig.signal_size, 8);
+    assert_eq!(sig.receivers, ["X"]);
+}
+
+#[test]
+fn flags_resolve_defaults_and_enum_labels() {
+    let input = r#"
+BO_ 1 Default: 8 X
+ SG_ S : 0|8@1+ (1,0) [0|0] "" X
+BO_ 2 Explicit: 8 X
+ SG_ T : 0|8@1+ (1,0) [0|0] "" X
+
+BA_DEF_ BO_ "NmMessage" ENUM "Yes","No";
+BA_DEF_ BO_ "DiagResponse" ENUM "No","Yes";
+BA_DEF_DEF_ "NmMessage" "Yes";
+BA_DEF_DEF_ "DiagResponse" "No";
+BA_ "NmMessage" BO_ 2 1;
+BA_ "DiagResponse" BO_ 2 1;
+"#;
+    let dbc = Dbc::try_from(input).unwrap();
+    let (default, explicit) = (&dbc.messages[0], &dbc.messages[1]);
+    assert_eq!(default.nm_message, Some(true));
+    assert!(default.is_network_management() && !default.is_diagnostic());
+    assert_eq!(explicit.nm_message, Some(false));
+    assert_eq!(explicit.diag_response, Some(true));
+    assert!(!explicit.is_network_management() && explicit.is_diagnostic());
+
+    for msg in &dbc.messages {
+        assert_eq!(&Message::from_dbc_block(&msg.to_dbc_block()).unwrap(), msg);
+    }
+}
+
+#[test]
+fn physical_initial_value_sources() {
+    let input = r#"
+BO_ 1 A: 8 X
+ SG_ Raw : 0|8@1+ (0.5,-10) [0|0] "" X
+ SG_ Phys : 8|8@1+ (0.5,-10) [0|0] "" X
+ SG_ Both : 16|8@1+ (0.5,-10) [0|0] "" X
+
+BA_DEF_ SG_ "GenSigStartValue" INT 0 255;
+BA_DEF_ SG_ "GenSigStartValuePhys" FLOAT -10 117.5;
+BA_ "GenSigStartValue" SG_ 1 Raw 40;
+BA_ "GenSigStartValuePhys" SG_ 1 Phys 2.5;
+BA_ "GenSigStartValue" SG_ 1 Both 40;
+BA_ "GenSigStartValuePhys" SG_ 1 Both 7.5;
+"#;
+    let dbc = Dbc::try_from(input).unwrap();
+    let initial: Vec<f64> = dbc.messages[0]
+        .signals
+        .iter()
+        .map(Signal::physical_initial_value)
+        .collect();
+    // The already scaled value wins over the raw one
+    assert_eq!(initial, [10.0, 2.5, 7.5]);
+}
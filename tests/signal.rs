@@ -0,0 +1,112 @@
+use rs_dbc::{ByteOrder, Signal, extract_bits, insert_bits};
+
+fn motorola(start_bit: u64, size: u64) -> Signal {
+    let mut sig = Signal::new("Sig", start_bit, size);
+    sig.byte_order = ByteOrder::Motorola;
+    sig
+}
+
+#[test]
+fn vector_start_bit_of_motorola_signals() {
+    assert_eq!(motorola(7, 16).vector_start_bit(), 8);
+    assert_eq!(motorola(39, 12).vector_start_bit(), 44);
+    assert_eq!(motorola(3, 4).vector_start_bit(), 0);
+    assert_eq!(Signal::new("Sig", 12, 8).vector_start_bit(), 12);
+}
+
+#[test]
+fn vector_start_bit_follows_layout_changes() {
+    let mut sig = Signal::new("Sig", 7, 16);
+    assert_eq!(sig.vector_start_bit(), 7);
+    sig.byte_order = ByteOrder::Motorola;
+    assert_eq!(sig.vector_start_bit(), 8);
+    sig.signal_size = 8;
+    assert_eq!(sig.vector_start_bit(), 0);
+}
+
+#[test]
+fn canonical_from_vector_inverts_vector_start_bit() {
+    for (start_bit, size) in [(7, 16), (39, 12), (3, 4), (23, 1), (7, 64)] {
+        let sig = motorola(start_bit, size);
+        let vector_bit = sig.vector_start_bit();
+        assert_eq!(
+            Signal::canonical_from_vector(vector_bit, size, ByteOrder::Motorola),
+            start_bit,
+            "Motorola {start_bit}|{size}"
+        );
+    }
+    for (start_bit, size) in [(0, 8), (12, 4), (60, 16)] {
+        let sig = Signal::new("Sig", start_bit, size);
+        assert_eq!(sig.vector_start_bit(), start_bit);
+        assert_eq!(
+            Signal::canonical_from_vector(start_bit, size, ByteOrder::Intel),
+            start_bit
+        );
+    }
+}
+
+#[test]
+fn extract_bits_boundaries() {
+    let ones = [0xFF; 8];
+    assert_eq!(extract_bits(&ones, 0, 64, ByteOrder::Intel, false), -1);
+    assert_eq!(extract_bits(&ones, 7, 64, ByteOrder::Motorola, false), -1);
+    assert_eq!(extract_bits(&ones, 0, 8, ByteOrder::Intel, true), -1);
+    assert_eq!(extract_bits(&ones, 0, 8, ByteOrder::Intel, false), 0xFF);
+
+    let top = [0, 0, 0, 0, 0, 0, 0, 0x80];
+    assert_eq!(extract_bits(&top, 63, 1, ByteOrder::Intel, false), 1);
+    assert_eq!(extract_bits(&top, 62, 1, ByteOrder::Intel, false), 0);
+    assert_eq!(extract_bits(&[0x01], 0, 1, ByteOrder::Motorola, false), 1);
+
+    let data = [0x12, 0x34, 0x56];
+    assert_eq!(extract_bits(&data, 0, 16, ByteOrder::Intel, false), 0x3412);
+    assert_eq!(
+        extract_bits(&data, 7, 16, ByteOrder::Motorola, false),
+        0x1234
+    );
+    assert_eq!(extract_bits(&data, 4, 8, ByteOrder::Intel, false), 0x41);
+    assert_eq!(
+        extract_bits(&data, 3, 12, ByteOrder::Motorola, false),
+        0x234
+    );
+
+    // Zero and oversized widths and bits past the payload read as zero
+    assert_eq!(extract_bits(&ones, 0, 0, ByteOrder::Intel, false), 0);
+    assert_eq!(extract_bits(&ones, 0, 65, ByteOrder::Intel, false), 0);
+    assert_eq!(extract_bits(&ones, 64, 8, ByteOrder::Intel, false), 0);
+    assert_eq!(extract_bits(&ones, 60, 8, ByteOrder::Intel, false), 0x0F);
+}
+
+#[test]
+fn insert_bits_inverts_extract_bits() {
+    for (start_bit, size, byte_order) in [
+        (0, 64, ByteOrder::Intel),
+        (7, 64, ByteOrder::Motorola),
+        (5, 11, ByteOrder::Intel),
+        (21, 11, ByteOrder::Motorola),
+    ] {
+        let mut data = [0u8; 8];
+        insert_bits(&mut data, start_bit, size, byte_order, -3);
+        assert_eq!(
+            extract_bits(&data, start_bit, size, byte_order, true),
+            -3,
+            "{start_bit}|{size} {byte_order}"
+        );
+    }
+}
+
+#[cfg(feature = "decimal")]
+#[test]
+fn raw_to_decimal_is_exact_or_none() {
+    let mut sig = Signal::new("Sig", 0, 8);
+    sig.factor = 0.1;
+    sig.offset = -40.0;
+    assert_eq!(sig.raw_to_decimal(3).unwrap().to_string(), "-39.7");
+
+    sig.factor = 1e300;
+    assert_eq!(sig.raw_to_decimal(3), None);
+    sig.factor = 1e-30;
+    assert_eq!(sig.raw_to_decimal(3), None);
+    sig.factor = 1e20;
+    assert_eq!(sig.raw_to_decimal(i64::MAX), None);
+}
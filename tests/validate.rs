@@ -0,0 +1,40 @@
+use rs_dbc::{Dbc, Severity, ValidationWarning};
+
+fn warnings(signals: &str) -> Vec<ValidationWarning> {
+    let input = format!("BU_: X\n\nBO_ 1 Msg: 8 X\n{}\n", signals);
+    Dbc::try_from(input.as_str()).unwrap().validate()
+}
+
+#[test]
+fn invalid_signal_sizes_are_reported() {
+    let found = warnings(
+        r#" SG_ Empty : 0|0@1+ (1,0) [0|0] "" X
+ SG_ Wide : 0|65@1+ (1,0) [0|0] "" X
+ SG_ Fine : 0|64@1+ (1,0) [0|0] "" X"#,
+    );
+    let size = |signal: &str, signal_size| ValidationWarning::InvalidSignalSize {
+        message: "Msg".to_string(),
+        signal: signal.to_string(),
+        signal_size,
+    };
+    assert_eq!(found, [size("Empty", 0), size("Wide", 65)]);
+    assert!(found.iter().all(|w| w.severity() == Severity::Error));
+}
+
+#[test]
+fn zero_factor_is_reported() {
+    let found = warnings(r#" SG_ Flat : 0|8@1+ (0,5) [0|0] "" X"#);
+    assert_eq!(
+        found,
+        [ValidationWarning::ZeroFactor {
+            message: "Msg".to_string(),
+            signal: "Flat".to_string(),
+        }]
+    );
+    assert_eq!(found[0].severity(), Severity::Warning);
+
+    let dbc = Dbc::try_from("BO_ 1 Msg: 8 X\n SG_ Flat : 0|8@1+ (0,5) [0|0] \"\" X\n").unwrap();
+    let signal = &dbc.messages[0].signals[0];
+    assert_eq!(signal.physical_to_raw(5.0), 0);
+    assert_eq!(signal.physical_to_raw(42.0), 0);
+}
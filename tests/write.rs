@@ -0,0 +1,223 @@
+use rs_dbc::{AttributeValue, AttributeValueType, Dbc, Error, Message, MessageID, RelationTarget};
+
+fn example(name: &str) -> Dbc {
+    let path = format!("{}/examples/{}", env!("CARGO_MANIFEST_DIR"), name);
+    Dbc::try_from(std::fs::read_to_string(path).unwrap().as_str()).unwrap()
+}
+
+#[test]
+fn message_block_round_trips() {
+    for file in ["sample.dbc", "simple.dbc"] {
+        for msg in example(file).messages {
+            let parsed = Message::from_dbc_block(&msg.to_dbc_block()).unwrap();
+            assert_eq!(parsed, msg, "{} in {}", msg.message_name, file);
+        }
+    }
+}
+
+#[test]
+fn message_block_keeps_frame_format() {
+    let input = r#"
+BO_ 1 Fd: 64 X
+ SG_ A : 0|8@1+ (1,0) [0|0] "" X
+
+BA_DEF_ BO_ "VFrameFormat" ENUM "StandardCAN","ExtendedCAN","reserved","J1939PG","reserved","reserved","reserved","reserved","reserved","reserved","reserved","reserved","reserved","reserved","StandardCAN_FD","ExtendedCAN_FD";
+BA_ "VFrameFormat" BO_ 1 14;
+"#;
+    let msg = Dbc::try_from(input).unwrap().messages.remove(0);
+    assert!(msg.is_fd);
+    assert_eq!(Message::from_dbc_block(&msg.to_dbc_block()).unwrap(), msg);
+}
+
+#[test]
+fn hex_attribute_definitions_round_trip() {
+    let input = r#"
+BO_ 1 A: 8 X
+ SG_ S : 0|8@1+ (1,0) [0|0] "" X
+
+BA_DEF_ BU_ "NodeAddress" HEX 0 255;
+BA_DEF_ BO_ "GenMsgCycleTime" INT 0 65535;
+"#;
+    let dbc = Dbc::try_from(input).unwrap();
+    let value_type = |name: &str| {
+        dbc.attribute_definitions
+            .iter()
+            .find(|def| def.name == name)
+            .map(|def| def.value_type.clone())
+    };
+    assert_eq!(
+        value_type("NodeAddress"),
+        Some(AttributeValueType::Hex(0, 255))
+    );
+    assert_eq!(
+        value_type("GenMsgCycleTime"),
+        Some(AttributeValueType::Int(0, 65535))
+    );
+
+    let output = dbc.to_dbc_string();
+    assert!(output.contains(r#""NodeAddress" HEX 0 255;"#), "{output}");
+    assert_eq!(Dbc::try_from(output.as_str()).unwrap(), dbc);
+}
+
+#[test]
+fn renumbered_message_keeps_value_descriptions() {
+    let input = r#"
+BO_ 256 A: 8 X
+ SG_ S : 0|8@1+ (1,0) [0|0] "" X
+BO_ 257 B: 8 X
+ SG_ T : 0|8@1+ (1,0) [0|0] "" X
+
+VAL_ 256 S 0 "Off" 1 "On";
+"#;
+    let mut dbc = Dbc::try_from(input).unwrap();
+    let (old, new) = (MessageID::Standard(256), MessageID::Standard(512));
+    dbc.renumber_message(old, new).unwrap();
+    assert!(dbc.message_by_id(old).is_none());
+
+    let output = dbc.to_dbc_string();
+    assert!(output.contains("VAL_ 512 S "), "{output}");
+    let reparsed = Dbc::try_from(output.as_str()).unwrap();
+    let msg = reparsed.message_by_id(new).unwrap();
+    assert_eq!(msg.signals[0].value_descriptions[&1], "On");
+
+    assert!(matches!(
+        dbc.renumber_message(new, MessageID::Standard(257)),
+        Err(Error::DuplicateMessage(MessageID::Standard(257)))
+    ));
+    assert!(matches!(
+        dbc.renumber_message(old, MessageID::Standard(600)),
+        Err(Error::UnknownMessage(MessageID::Standard(256)))
+    ));
+    assert_eq!(dbc.messages[0].message_id, new);
+}
+
+#[test]
+fn network_and_env_var_attributes_round_trip() {
+    let input = r##"
+BU_: X
+
+BO_ 1 A: 8 X
+ SG_ S : 0|8@1+ (1,0) [0|0] "" X
+
+EV_ Speed: 0 [0|100] "" 0 1 DUMMY_NODE_VECTOR0 Vector__XXX;
+
+BA_DEF_  "DBName" STRING ;
+BA_DEF_ BO_ "GenMsgBackgroundColor" STRING ;
+BA_DEF_ EV_ "EvScale" INT 0 10;
+BA_ "DBName" "Powertrain";
+BA_ "GenMsgBackgroundColor" BO_ 1 "#ffffff";
+BA_ "EvScale" EV_ Speed 3;
+"##;
+    let dbc = Dbc::try_from(input).unwrap();
+    let string = |value: &str| AttributeValue::String(value.to_string());
+    assert_eq!(dbc.attributes["DBName"], string("Powertrain"));
+    assert!(!dbc.attributes.contains_key("GenMsgBackgroundColor"));
+    assert_eq!(
+        dbc.messages[0].attributes["GenMsgBackgroundColor"],
+        string("#ffffff")
+    );
+    assert_eq!(
+        dbc.env_vars[0].attributes["EvScale"],
+        AttributeValue::Int(3)
+    );
+
+    let output = dbc.to_dbc_string();
+    assert!(
+        output.contains(r##"BA_ "GenMsgBackgroundColor" BO_ 1 "#ffffff";"##),
+        "{output}"
+    );
+    assert!(output.contains(r#"BA_ "EvScale" EV_ Speed 3;"#), "{output}");
+    assert_eq!(Dbc::try_from(output.as_str()).unwrap(), dbc);
+}
+
+#[test]
+fn skipped_statements_round_trip() {
+    let skipped = [
+        r#"CM_ "network comment";"#,
+        r#"CM_ EV_ Ev "Environment comment";"#,
+        r#"VAL_ Ev 0 "zero" 1 "one" ;"#,
+        r#"BA_DEF_DEF_ "Orphan" 5;"#,
+    ];
+    let input = format!(
+        "BU_: X\n\nBO_ 1 A: 8 X\n SG_ S : 0|8@1+ (1,0) [0|0] \"\" X\n\n\
+         EV_ Ev: 0 [0|1] \"\" 0 1 DUMMY_NODE_VECTOR0 Vector__XXX;\n\n\
+         CM_ BO_ 1 \"Message comment\";\n\
+         VAL_ 1 S 0 \"Off\" ;\n\
+         {}\n",
+        skipped.join("\n")
+    );
+    let dbc = Dbc::try_from(input.as_str()).unwrap();
+    assert_eq!(dbc.raw_unparsed, skipped);
+    assert_eq!(dbc.messages[0].comment.as_deref(), Some("Message comment"));
+
+    let output = dbc.to_dbc_string();
+    for statement in skipped {
+        assert!(
+            output.contains(statement),
+            "{statement} missing from {output}"
+        );
+    }
+    assert_eq!(Dbc::try_from(output.as_str()).unwrap(), dbc);
+}
+
+#[test]
+fn renamed_node_is_updated_everywhere() {
+    let input = r#"
+BU_: A B
+
+BO_ 1 M: 8 A
+ SG_ S : 0|8@1+ (1,0) [0|0] "" A,B
+
+BO_TX_BU_ 1 : B,A;
+
+EV_ Ev: 0 [0|10] "" 0 1 DUMMY_NODE_VECTOR0 A,B;
+
+BA_DEF_REL_ BU_BO_REL_ "R" INT 0 10;
+BA_REL_ "R" BU_BO_REL_ A 1 3;
+"#;
+    let mut dbc = Dbc::try_from(input).unwrap();
+    assert_eq!(dbc.rename_node("A", "Z"), 5);
+
+    let output = dbc.to_dbc_string();
+    assert!(!output.contains(" A"), "{output}");
+    let dbc = Dbc::try_from(output.as_str()).unwrap();
+    assert_eq!(dbc.nodes[0].name, "Z");
+    assert_eq!(dbc.messages[0].transmitter, "Z");
+    assert_eq!(dbc.messages[0].transmitters, ["B"]);
+    assert_eq!(dbc.messages[0].signals[0].receivers, ["Z", "B"]);
+    assert_eq!(dbc.env_vars[0].access_nodes, ["Z", "B"]);
+    assert_eq!(dbc.relation_attributes[0].node, "Z");
+}
+
+#[test]
+fn signal_groups_follow_reclassified_messages() {
+    let input = r#"
+BU_: A
+
+BO_ 256 M: 8 A
+ SG_ S : 0|8@1+ (1,0) [0|0] "" A
+
+BA_DEF_ BO_ "VFrameFormat" ENUM "StandardCAN","ExtendedCAN";
+BA_DEF_REL_ BU_BO_REL_ "R" INT 0 10;
+BA_ "VFrameFormat" BO_ 256 1;
+BA_REL_ "R" BU_BO_REL_ A 256 3;
+SIG_GROUP_ 256 Group 1 : S;
+"#;
+    let mut dbc = Dbc::try_from(input).unwrap();
+    let id = MessageID::Extended(256);
+    assert_eq!(dbc.messages[0].message_id, id);
+    assert_eq!(dbc.signal_groups[0].message_id, id);
+    assert_eq!(
+        dbc.relation_attributes[0].target,
+        RelationTarget::Message(id)
+    );
+    assert_eq!(Dbc::try_from(dbc.to_dbc_string().as_str()).unwrap(), dbc);
+
+    let new = MessageID::Extended(512);
+    dbc.renumber_message(id, new).unwrap();
+    assert_eq!(dbc.signal_groups[0].message_id, new);
+    assert_eq!(
+        dbc.relation_attributes[0].target,
+        RelationTarget::Message(new)
+    );
+}